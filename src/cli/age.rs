@@ -0,0 +1,127 @@
+use std::io::Write;
+
+use clap::Parser;
+use enum_dispatch::enum_dispatch;
+
+use crate::{
+    get_reader, get_writer, load_age_keys, process_age_decrypt, process_age_encrypt,
+    process_age_keygen, read_secret, CmdExector,
+};
+
+use super::verify_file;
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum AgeSubCommand {
+    #[command(about = "Generate an X25519 identity")]
+    Keygen(AgeKeygenOpts),
+
+    #[command(about = "Encrypt a file to age recipients or a passphrase")]
+    Encrypt(AgeEncryptOpts),
+
+    #[command(about = "Decrypt an age file")]
+    Decrypt(AgeDecryptOpts),
+}
+
+#[derive(Debug, Parser)]
+pub struct AgeKeygenOpts {
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct AgeEncryptOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+    /// age1... recipient, can be repeated
+    #[arg(short, long)]
+    pub recipient: Vec<String>,
+    /// file with one recipient per line, can be repeated
+    #[arg(short = 'R', long, value_parser = verify_file)]
+    pub recipients_file: Vec<String>,
+    /// encrypt with a passphrase (prompted, or RCLI_PASSPHRASE)
+    #[arg(short, long, conflicts_with_all = ["recipient", "recipients_file"])]
+    pub passphrase: bool,
+    /// write PEM-style ASCII armor instead of binary
+    #[arg(short, long)]
+    pub armor: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct AgeDecryptOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+    /// identity file, can be repeated
+    #[arg(short = 'k', long, value_parser = verify_file, required_unless_present = "passphrase")]
+    pub identity: Vec<String>,
+    /// decrypt with a passphrase (prompted, or RCLI_PASSPHRASE)
+    #[arg(short, long, conflicts_with = "identity")]
+    pub passphrase: bool,
+}
+
+impl CmdExector for AgeKeygenOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let (identity, recipient) = process_age_keygen();
+        let mut writer = get_writer(&self.output)?;
+        // 先收紧权限再写入私钥
+        #[cfg(unix)]
+        if self.output != "-" {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&self.output, std::fs::Permissions::from_mode(0o600))?;
+        }
+        writer.write_all(identity.as_bytes())?;
+        eprintln!("Public key: {}", recipient);
+        Ok(())
+    }
+}
+
+impl CmdExector for AgeEncryptOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let passphrase = if self.passphrase {
+            Some(read_secret("passphrase: ", "RCLI_PASSPHRASE")?)
+        } else {
+            None
+        };
+        let mut recipients = self.recipient;
+        for path in &self.recipients_file {
+            recipients.extend(load_age_keys(path)?);
+        }
+        if passphrase.is_none() && recipients.is_empty() {
+            return Err(anyhow::anyhow!(
+                "need at least one recipient or --passphrase"
+            ));
+        }
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_age_encrypt(
+            &mut reader,
+            &mut writer,
+            &recipients,
+            passphrase,
+            self.armor,
+        )?;
+        Ok(())
+    }
+}
+
+impl CmdExector for AgeDecryptOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let passphrase = if self.passphrase {
+            Some(read_secret("passphrase: ", "RCLI_PASSPHRASE")?)
+        } else {
+            None
+        };
+        let mut identities = Vec::new();
+        for path in &self.identity {
+            identities.extend(load_age_keys(path)?);
+        }
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_age_decrypt(&mut reader, &mut writer, &identities, passphrase)?;
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
-use std::{fmt, str::FromStr};
+use std::{fmt, io::Write, str::FromStr};
 
-use clap::Parser;
+use clap::{Args, Parser};
 use enum_dispatch::enum_dispatch;
 
-use crate::{process_decode, process_encode, CmdExector};
+use crate::{
+    get_reader, get_writer, process_datauri_decode, process_datauri_encode, process_decode,
+    process_encode, CmdExector,
+};
 
 use super::verify_file;
 
@@ -15,6 +18,15 @@ pub enum Base64SubCommand {
 
     #[command(name = "decode", about = "Decode a base64 file")]
     Decode(Base64DecodeOpts),
+
+    #[command(name = "datauri", about = "Wrap a file into a base64 data URI")]
+    DataUri(DataUriOpts),
+
+    #[command(
+        name = "datauri-decode",
+        about = "Parse a data URI and write out its payload"
+    )]
+    DataUriDecode(DataUriDecodeOpts),
 }
 
 #[derive(Debug, Parser)]
@@ -23,8 +35,35 @@ pub struct Base64EncodeOpts {
     #[arg(short, long, value_parser = verify_file, default_value = "-")]
     pub input: String,
 
+    // - 输出到 stdout
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
     #[arg(long, value_parser = parse_base64_format, default_value = "standard")]
     pub format: Base64Format,
+
+    #[command(flatten)]
+    pub style: Base64EncodeStyle,
+}
+
+/// Padding and line wrapping of the encoded output
+#[derive(Debug, Clone, Copy, Default, Args)]
+pub struct Base64EncodeStyle {
+    /// always pad the output with `=`
+    #[arg(long, conflicts_with = "no_pad")]
+    pub pad: bool,
+
+    /// never pad the output
+    #[arg(long)]
+    pub no_pad: bool,
+
+    /// wrap output lines at this many columns, 0 disables wrapping
+    #[arg(long, default_value_t = 0)]
+    pub wrap: usize,
+
+    /// MIME style: padded, wrapped at 76 columns with CRLF line endings
+    #[arg(long, conflicts_with_all = ["no_pad", "wrap"])]
+    pub mime: bool,
 }
 
 #[derive(Debug, Parser)]
@@ -32,14 +71,42 @@ pub struct Base64DecodeOpts {
     #[arg(short, long, value_parser = verify_file, default_value = "-")]
     pub input: String,
 
+    // 解码后的内容可能是二进制，原样写入
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
     #[arg(long, value_parser = parse_base64_format, default_value = "standard")]
     pub format: Base64Format,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Parser)]
+pub struct DataUriOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
+    /// MIME type, detected from the content or file extension if omitted
+    #[arg(short = 't', long)]
+    pub mime: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct DataUriDecodeOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Base64Format {
     Standard,
     UrlSafe,
+    /// detect the alphabet while decoding
+    Auto,
 }
 
 fn parse_base64_format(format: &str) -> Result<Base64Format, anyhow::Error> {
@@ -53,6 +120,7 @@ impl FromStr for Base64Format {
         match s {
             "standard" => Ok(Base64Format::Standard),
             "urlsafe" => Ok(Base64Format::UrlSafe),
+            "auto" => Ok(Base64Format::Auto),
             _ => Err(anyhow::anyhow!("Invalid format")),
         }
     }
@@ -63,6 +131,7 @@ impl From<Base64Format> for &'static str {
         match value {
             Base64Format::Standard => "standard",
             Base64Format::UrlSafe => "urlsafe",
+            Base64Format::Auto => "auto",
         }
     }
 }
@@ -75,17 +144,48 @@ impl fmt::Display for Base64Format {
 
 impl CmdExector for Base64EncodeOpts {
     async fn execute(self) -> anyhow::Result<()> {
-        let ret = process_encode(&self.input, self.format)?;
-        println!("{}", ret);
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_encode(&mut reader, &mut writer, self.format, self.style)?;
+        // 换行输出时最后一行已经带了换行符
+        if !self.style.mime && self.style.wrap == 0 {
+            writeln!(writer)?;
+        }
         Ok(())
     }
 }
 
 impl CmdExector for Base64DecodeOpts {
     async fn execute(self) -> anyhow::Result<()> {
-        let decoded = process_decode(&self.input, self.format)?;
-        let decoded = String::from_utf8(decoded)?;
-        println!("decoded: {}", decoded);
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_decode(&mut reader, &mut writer, self.format)?;
+        Ok(())
+    }
+}
+
+impl CmdExector for DataUriOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_datauri_encode(
+            &mut reader,
+            &mut writer,
+            self.mime.as_deref(),
+            Some(&self.input),
+        )?;
+        writeln!(writer)?;
+        Ok(())
+    }
+}
+
+impl CmdExector for DataUriDecodeOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        let mime = process_datauri_decode(&mut reader, &mut writer)?;
+        // 内容可能写到了 stdout，类型信息输出到 stderr
+        eprintln!("mime: {}", mime);
         Ok(())
     }
 }
@@ -0,0 +1,156 @@
+use std::{fmt, io::Write, str::FromStr};
+
+use clap::Parser;
+use enum_dispatch::enum_dispatch;
+
+use crate::{
+    get_reader, get_writer, process_codec_decode, process_codec_encode, CmdExector, CodecOptions,
+};
+
+use super::verify_file;
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum CodecSubCommand {
+    #[command(name = "encode", about = "Encode a file to a binary-to-text format")]
+    Encode(CodecEncodeOpts),
+
+    #[command(name = "decode", about = "Decode a binary-to-text encoded file")]
+    Decode(CodecDecodeOpts),
+}
+
+#[derive(Debug, Parser)]
+pub struct CodecEncodeOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
+    #[arg(long, value_parser = parse_codec_format)]
+    pub format: CodecFormat,
+
+    /// hex: use uppercase digits
+    #[arg(long)]
+    pub upper: bool,
+
+    /// hex: separator between bytes, e.g. ":" or " "
+    #[arg(long, default_value = "")]
+    pub separator: String,
+
+    /// base58: append a checksum (Base58Check)
+    #[arg(long)]
+    pub check: bool,
+
+    /// base32/base64: don't pad the output
+    #[arg(long)]
+    pub no_pad: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct CodecDecodeOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
+    #[arg(long, value_parser = parse_codec_format)]
+    pub format: CodecFormat,
+
+    /// hex: separator between bytes to strip
+    #[arg(long, default_value = "")]
+    pub separator: String,
+
+    /// base58: verify and strip the checksum (Base58Check)
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodecFormat {
+    Base64,
+    Base64Url,
+    Hex,
+    Base32,
+    Base32Crockford,
+    ZBase32,
+    Base58,
+    Ascii85,
+    Z85,
+}
+
+fn parse_codec_format(format: &str) -> Result<CodecFormat, anyhow::Error> {
+    format.parse()
+}
+
+impl FromStr for CodecFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "base64" => Ok(CodecFormat::Base64),
+            "base64url" => Ok(CodecFormat::Base64Url),
+            "hex" => Ok(CodecFormat::Hex),
+            "base32" => Ok(CodecFormat::Base32),
+            "base32-crockford" => Ok(CodecFormat::Base32Crockford),
+            "z-base-32" => Ok(CodecFormat::ZBase32),
+            "base58" => Ok(CodecFormat::Base58),
+            "ascii85" => Ok(CodecFormat::Ascii85),
+            "z85" => Ok(CodecFormat::Z85),
+            _ => Err(anyhow::anyhow!("Invalid format: {}", s)),
+        }
+    }
+}
+
+impl From<CodecFormat> for &'static str {
+    fn from(value: CodecFormat) -> Self {
+        match value {
+            CodecFormat::Base64 => "base64",
+            CodecFormat::Base64Url => "base64url",
+            CodecFormat::Hex => "hex",
+            CodecFormat::Base32 => "base32",
+            CodecFormat::Base32Crockford => "base32-crockford",
+            CodecFormat::ZBase32 => "z-base-32",
+            CodecFormat::Base58 => "base58",
+            CodecFormat::Ascii85 => "ascii85",
+            CodecFormat::Z85 => "z85",
+        }
+    }
+}
+
+impl fmt::Display for CodecFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Into::<&str>::into(*self))
+    }
+}
+
+impl CmdExector for CodecEncodeOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let opts = CodecOptions {
+            upper: self.upper,
+            separator: self.separator,
+            check: self.check,
+            no_pad: self.no_pad,
+        };
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_codec_encode(&mut reader, &mut writer, self.format, &opts)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+}
+
+impl CmdExector for CodecDecodeOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let opts = CodecOptions {
+            separator: self.separator,
+            check: self.check,
+            ..Default::default()
+        };
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_codec_decode(&mut reader, &mut writer, self.format, &opts)?;
+        Ok(())
+    }
+}
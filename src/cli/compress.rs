@@ -0,0 +1,119 @@
+use std::{fmt, io::Write, str::FromStr};
+
+use clap::Parser;
+
+use crate::{get_reader, get_writer, process_compress, process_decompress, CmdExector};
+
+use super::verify_file;
+
+#[derive(Debug, Parser)]
+pub struct CompressOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
+    #[arg(long, value_parser = parse_compress_format, default_value = "gzip")]
+    pub format: CompressFormat,
+
+    /// compression level, the range depends on the format
+    #[arg(short, long)]
+    pub level: Option<u32>,
+
+    /// base64 encode the compressed output
+    #[arg(long)]
+    pub base64: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct DecompressOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
+    #[arg(long, value_parser = parse_compress_format, default_value = "gzip")]
+    pub format: CompressFormat,
+
+    /// the input is base64 encoded compressed data
+    #[arg(long)]
+    pub base64: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressFormat {
+    Gzip,
+    Zlib,
+    Deflate,
+    Zstd,
+    Brotli,
+    Xz,
+}
+
+fn parse_compress_format(format: &str) -> Result<CompressFormat, anyhow::Error> {
+    format.parse()
+}
+
+impl FromStr for CompressFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gzip" | "gz" => Ok(CompressFormat::Gzip),
+            "zlib" => Ok(CompressFormat::Zlib),
+            "deflate" => Ok(CompressFormat::Deflate),
+            "zstd" | "zst" => Ok(CompressFormat::Zstd),
+            "brotli" | "br" => Ok(CompressFormat::Brotli),
+            "xz" => Ok(CompressFormat::Xz),
+            _ => Err(anyhow::anyhow!("Invalid format: {}", s)),
+        }
+    }
+}
+
+impl From<CompressFormat> for &'static str {
+    fn from(value: CompressFormat) -> Self {
+        match value {
+            CompressFormat::Gzip => "gzip",
+            CompressFormat::Zlib => "zlib",
+            CompressFormat::Deflate => "deflate",
+            CompressFormat::Zstd => "zstd",
+            CompressFormat::Brotli => "brotli",
+            CompressFormat::Xz => "xz",
+        }
+    }
+}
+
+impl fmt::Display for CompressFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Into::<&str>::into(*self))
+    }
+}
+
+impl CmdExector for CompressOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_compress(
+            &mut reader,
+            &mut writer,
+            self.format,
+            self.level,
+            self.base64,
+        )?;
+        if self.base64 {
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl CmdExector for DecompressOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_decompress(&mut reader, &mut writer, self.format, self.base64)?;
+        Ok(())
+    }
+}
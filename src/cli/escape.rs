@@ -0,0 +1,253 @@
+use std::{fmt, io::Write, str::FromStr};
+
+use clap::Parser;
+use enum_dispatch::enum_dispatch;
+
+use crate::{
+    get_reader, get_writer, process_html_escape, process_html_unescape, process_unicode_escape,
+    process_unicode_unescape, process_url_decode, process_url_encode, CmdExector,
+};
+
+use super::verify_file;
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum UrlSubCommand {
+    #[command(name = "encode", about = "Percent-encode a URL component")]
+    Encode(UrlEncodeOpts),
+
+    #[command(name = "decode", about = "Decode a percent-encoded string")]
+    Decode(UrlDecodeOpts),
+}
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum HtmlSubCommand {
+    #[command(name = "escape", about = "Escape text for use in HTML")]
+    Escape(HtmlEscapeOpts),
+
+    #[command(
+        name = "unescape",
+        about = "Replace HTML entities with their characters"
+    )]
+    Unescape(HtmlUnescapeOpts),
+}
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum UnicodeSubCommand {
+    #[command(
+        name = "escape",
+        about = "Escape text as a JSON or Rust string literal body"
+    )]
+    Escape(UnicodeEscapeOpts),
+
+    #[command(name = "unescape", about = "Undo JSON or Rust string escapes")]
+    Unescape(UnicodeUnescapeOpts),
+}
+
+#[derive(Debug, Parser)]
+pub struct UrlEncodeOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
+    /// which part of the URL the input is: component, path, query or form
+    #[arg(long, value_parser = parse_url_component, default_value = "component")]
+    pub component: UrlComponent,
+}
+
+#[derive(Debug, Parser)]
+pub struct UrlDecodeOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
+    /// form also decodes `+` as a space
+    #[arg(long, value_parser = parse_url_component, default_value = "component")]
+    pub component: UrlComponent,
+}
+
+#[derive(Debug, Parser)]
+pub struct HtmlEscapeOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
+    /// also escape every non-ASCII character as a numeric reference
+    #[arg(long)]
+    pub ascii: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct HtmlUnescapeOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct UnicodeEscapeOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
+    #[arg(long, value_parser = parse_unicode_style, default_value = "json")]
+    pub style: UnicodeStyle,
+}
+
+#[derive(Debug, Parser)]
+pub struct UnicodeUnescapeOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UrlComponent {
+    Component,
+    Path,
+    Query,
+    Form,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnicodeStyle {
+    Json,
+    Rust,
+}
+
+fn parse_url_component(component: &str) -> Result<UrlComponent, anyhow::Error> {
+    component.parse()
+}
+
+fn parse_unicode_style(style: &str) -> Result<UnicodeStyle, anyhow::Error> {
+    style.parse()
+}
+
+impl FromStr for UrlComponent {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "component" => Ok(UrlComponent::Component),
+            "path" => Ok(UrlComponent::Path),
+            "query" => Ok(UrlComponent::Query),
+            "form" => Ok(UrlComponent::Form),
+            _ => Err(anyhow::anyhow!("Invalid URL component: {}", s)),
+        }
+    }
+}
+
+impl From<UrlComponent> for &'static str {
+    fn from(value: UrlComponent) -> Self {
+        match value {
+            UrlComponent::Component => "component",
+            UrlComponent::Path => "path",
+            UrlComponent::Query => "query",
+            UrlComponent::Form => "form",
+        }
+    }
+}
+
+impl fmt::Display for UrlComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Into::<&str>::into(*self))
+    }
+}
+
+impl FromStr for UnicodeStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(UnicodeStyle::Json),
+            "rust" => Ok(UnicodeStyle::Rust),
+            _ => Err(anyhow::anyhow!("Invalid style: {}", s)),
+        }
+    }
+}
+
+impl From<UnicodeStyle> for &'static str {
+    fn from(value: UnicodeStyle) -> Self {
+        match value {
+            UnicodeStyle::Json => "json",
+            UnicodeStyle::Rust => "rust",
+        }
+    }
+}
+
+impl fmt::Display for UnicodeStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Into::<&str>::into(*self))
+    }
+}
+
+impl CmdExector for UrlEncodeOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_url_encode(&mut reader, &mut writer, self.component)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+}
+
+impl CmdExector for UrlDecodeOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_url_decode(&mut reader, &mut writer, self.component)?;
+        Ok(())
+    }
+}
+
+impl CmdExector for HtmlEscapeOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_html_escape(&mut reader, &mut writer, self.ascii)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+}
+
+impl CmdExector for HtmlUnescapeOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_html_unescape(&mut reader, &mut writer)?;
+        Ok(())
+    }
+}
+
+impl CmdExector for UnicodeEscapeOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_unicode_escape(&mut reader, &mut writer, self.style)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+}
+
+impl CmdExector for UnicodeUnescapeOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_unicode_unescape(&mut reader, &mut writer)?;
+        Ok(())
+    }
+}
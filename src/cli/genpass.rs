@@ -1,9 +1,68 @@
 use clap::Parser;
+use enum_dispatch::enum_dispatch;
 
-use crate::{process_genpass, CmdExector};
+use crate::{
+    load_pass_policy, process_derive_pass, process_genpass, process_genpass_policy,
+    process_genpass_pronounceable, process_genpin, read_secret, CmdExector,
+};
+
+use super::verify_file;
 
 #[derive(Debug, Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct GenPassOpts {
+    #[command(subcommand)]
+    pub cmd: Option<GenPassSubCommand>,
+
+    /// defaults to 16, with --policy it's clamped into the policy's bounds
+    #[arg(short, long)]
+    pub length: Option<u8>,
+
+    #[arg(long, default_value_t = true)]
+    pub uppercase: bool,
+
+    #[arg(long, default_value_t = true)]
+    pub lowercase: bool,
+
+    #[arg(long, default_value_t = true)]
+    pub number: bool,
+
+    #[arg(long, default_value_t = true)]
+    pub symbol: bool,
+
+    /// name of the policy to generate the password for
+    #[arg(long, requires = "policy_file")]
+    pub policy: Option<String>,
+
+    /// TOML or YAML file describing the policies
+    #[arg(long, value_parser = verify_file, requires = "policy")]
+    pub policy_file: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum GenPassSubCommand {
+    #[command(about = "Derive a site password from a master secret")]
+    Derive(GenPassDeriveOpts),
+
+    #[command(about = "Generate a pronounceable password")]
+    Pronounce(GenPassPronounceOpts),
+
+    #[command(about = "Generate a numeric PIN without weak patterns")]
+    Pin(GenPinOpts),
+}
+
+#[derive(Debug, Parser)]
+pub struct GenPassDeriveOpts {
+    #[arg(long)]
+    pub site: String,
+
+    #[arg(long)]
+    pub user: String,
+
+    #[arg(long, default_value_t = 1)]
+    pub counter: u32,
+
     #[arg(short, long, default_value_t = 16)]
     pub length: u8,
 
@@ -20,10 +79,40 @@ pub struct GenPassOpts {
     pub symbol: bool,
 }
 
+#[derive(Debug, Parser)]
+pub struct GenPassPronounceOpts {
+    #[arg(short, long, default_value_t = 4)]
+    pub syllables: u8,
+
+    /// number of digits appended at the end
+    #[arg(short, long, default_value_t = 0)]
+    pub digits: u8,
+
+    #[arg(long, default_value_t = false)]
+    pub capitalize: bool,
+
+    #[arg(long, default_value = "-")]
+    pub separator: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct GenPinOpts {
+    #[arg(short, long, default_value_t = 6)]
+    pub length: u8,
+}
+
 impl CmdExector for GenPassOpts {
     async fn execute(self) -> anyhow::Result<()> {
+        if let Some(cmd) = self.cmd {
+            return cmd.execute().await;
+        }
+        if let (Some(name), Some(file)) = (&self.policy, &self.policy_file) {
+            let policy = load_pass_policy(file, name)?;
+            println!("{}", process_genpass_policy(self.length, &policy)?);
+            return Ok(());
+        }
         let ret = process_genpass(
-            self.length,
+            self.length.unwrap_or(16),
             self.uppercase,
             self.lowercase,
             self.number,
@@ -34,3 +123,47 @@ impl CmdExector for GenPassOpts {
         Ok(())
     }
 }
+
+impl CmdExector for GenPassDeriveOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        // 主密码优先从环境变量读取，否则在终端中输入
+        let master = read_secret("master secret: ", "RCLI_MASTER_SECRET")?;
+        let ret = process_derive_pass(
+            master.as_bytes(),
+            &self.site,
+            &self.user,
+            self.counter,
+            self.length,
+            self.uppercase,
+            self.lowercase,
+            self.number,
+            self.symbol,
+        )?;
+        println!("{}", ret);
+        Ok(())
+    }
+}
+
+impl CmdExector for GenPassPronounceOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let (ret, entropy) = process_genpass_pronounceable(
+            self.syllables,
+            self.digits,
+            self.capitalize,
+            &self.separator,
+        )?;
+        println!("{}", ret);
+        // 熵输出到 stderr，方便直接通过管道使用生成的密码
+        eprintln!("entropy: {:.1} bits", entropy);
+        Ok(())
+    }
+}
+
+impl CmdExector for GenPinOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let (ret, entropy) = process_genpin(self.length)?;
+        println!("{}", ret);
+        eprintln!("entropy: {:.1} bits", entropy);
+        Ok(())
+    }
+}
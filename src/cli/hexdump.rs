@@ -0,0 +1,66 @@
+use clap::Parser;
+
+use crate::{
+    get_reader, get_writer, process_hexdump, process_hexdump_reverse, CmdExector, HexdumpOptions,
+};
+
+use super::verify_file;
+
+#[derive(Debug, Parser)]
+pub struct HexdumpOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+
+    /// bytes per line
+    #[arg(short = 'c', long, default_value_t = 16, value_parser = clap::value_parser!(u16).range(1..=256))]
+    pub cols: u16,
+
+    /// bytes per group, 0 disables grouping
+    #[arg(short, long, default_value_t = 2)]
+    pub group: usize,
+
+    /// start at this byte offset, decimal or 0x-prefixed hex
+    #[arg(short, long, value_parser = parse_offset, default_value = "0", conflicts_with = "reverse")]
+    pub seek: u64,
+
+    /// only dump this many bytes, decimal or 0x-prefixed hex
+    #[arg(short, long, value_parser = parse_offset, conflicts_with = "reverse")]
+    pub len: Option<u64>,
+
+    /// use uppercase hex digits
+    #[arg(short, long)]
+    pub upper: bool,
+
+    /// turn a hexdump back into binary
+    #[arg(short, long)]
+    pub reverse: bool,
+}
+
+fn parse_offset(s: &str) -> Result<u64, anyhow::Error> {
+    let n = match s.strip_prefix("0x").or(s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16)?,
+        None => s.parse()?,
+    };
+    Ok(n)
+}
+
+impl CmdExector for HexdumpOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        if self.reverse {
+            return process_hexdump_reverse(&mut reader, &mut writer);
+        }
+        let opts = HexdumpOptions {
+            width: self.cols as usize,
+            group: self.group,
+            offset: self.seek,
+            length: self.len,
+            upper: self.upper,
+        };
+        process_hexdump(&mut reader, &mut writer, &opts)
+    }
+}
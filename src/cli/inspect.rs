@@ -0,0 +1,28 @@
+use clap::Parser;
+
+use crate::{get_reader, process_inspect, CmdExector};
+
+use super::verify_file;
+
+#[derive(Debug, Parser)]
+pub struct InspectOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+
+    /// stop after decoding this many layers
+    #[arg(long, default_value_t = 8)]
+    pub max_layers: usize,
+
+    /// bytes of binary output to show in the hexdump preview
+    #[arg(long, default_value_t = 256)]
+    pub preview: usize,
+}
+
+impl CmdExector for InspectOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let mut reader = get_reader(&self.input)?;
+        let report = process_inspect(&mut reader, self.max_layers, self.preview)?;
+        print!("{}", report);
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use clap::Parser;
+use enum_dispatch::enum_dispatch;
+
+use crate::{
+    get_reader, process_minisign_keygen, process_minisign_sign, process_minisign_verify,
+    read_key_passphrase, write_private, CmdExector,
+};
+
+use super::{text::new_key_passphrase, verify_file};
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum MinisignSubCommand {
+    #[command(about = "Generate a minisign key pair")]
+    Keygen(MinisignKeygenOpts),
+
+    #[command(about = "Sign a file, writes <input>.minisig")]
+    Sign(MinisignSignOpts),
+
+    #[command(about = "Verify a minisign signature and print its trusted comment")]
+    Verify(MinisignVerifyOpts),
+}
+
+#[derive(Debug, Parser)]
+pub struct MinisignKeygenOpts {
+    #[arg(short, long, default_value = "minisign.key")]
+    pub secret_key: String,
+    #[arg(short, long, default_value = "minisign.pub")]
+    pub public_key: String,
+    /// encrypt the secret key (RCLI_KEY_PASSPHRASE_FILE, RCLI_KEY_PASSPHRASE or prompted)
+    #[arg(long)]
+    pub passphrase: bool,
+    /// overwrite existing key files
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct MinisignSignOpts {
+    #[arg(short, long, value_parser = verify_file)]
+    pub input: String,
+    #[arg(short, long, value_parser = verify_file, default_value = "minisign.key")]
+    pub secret_key: String,
+    /// defaults to <input>.minisig
+    #[arg(short, long)]
+    pub output: Option<String>,
+    /// defaults to timestamp:<now>\tfile:<name>\thashed like minisign
+    #[arg(short, long)]
+    pub trusted_comment: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct MinisignVerifyOpts {
+    #[arg(short, long, value_parser = verify_file)]
+    pub input: String,
+    #[arg(short, long, value_parser = verify_file, required_unless_present = "public_key_string")]
+    pub public_key: Option<String>,
+    /// base64 public key, e.g. from a project's README
+    #[arg(short = 'P', long, conflicts_with = "public_key")]
+    pub public_key_string: Option<String>,
+    /// defaults to <input>.minisig
+    #[arg(short = 'x', long, value_parser = verify_file)]
+    pub sig: Option<String>,
+}
+
+impl CmdExector for MinisignKeygenOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let passphrase = new_key_passphrase(self.passphrase)?;
+        let (sk, pk) = process_minisign_keygen(passphrase.as_deref())?;
+        write_key_pair(&self.secret_key, &sk, &self.public_key, &pk, self.force)?;
+        print!("{}", pk);
+        Ok(())
+    }
+}
+
+impl CmdExector for MinisignSignOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let output = output_path(self.output, &self.input, "minisig")?;
+        let trusted_comment = self.trusted_comment.unwrap_or_else(|| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let name = Path::new(&self.input)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            format!("timestamp:{}\tfile:{}\thashed", now, name)
+        });
+        let secret_key = fs::read_to_string(&self.secret_key)?;
+        let mut reader = get_reader(&self.input)?;
+        let sig = process_minisign_sign(
+            &mut reader,
+            &secret_key,
+            &read_key_passphrase,
+            &trusted_comment,
+        )?;
+        fs::write(&output, sig)?;
+        eprintln!("signature written to {}", output);
+        Ok(())
+    }
+}
+
+impl CmdExector for MinisignVerifyOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let public_key = match (self.public_key, self.public_key_string) {
+            (_, Some(key)) => key,
+            (Some(path), None) => fs::read_to_string(path)?,
+            (None, None) => unreachable!("clap requires one of them"),
+        };
+        let sig = output_path(self.sig, &self.input, "minisig")?;
+        let sig = fs::read_to_string(sig)?;
+        let mut reader = get_reader(&self.input)?;
+        match process_minisign_verify(&mut reader, &public_key, &sig)? {
+            Some(trusted_comment) => {
+                println!("verify: true");
+                println!("trusted comment: {}", trusted_comment);
+            }
+            None => println!("verify: false"),
+        }
+        Ok(())
+    }
+}
+
+/// Signatures live next to the input unless a path is given
+pub(super) fn output_path(path: Option<String>, input: &str, ext: &str) -> anyhow::Result<String> {
+    match path {
+        Some(path) => Ok(path),
+        None if input == "-" => Err(anyhow::anyhow!(
+            "a signature path is required when reading stdin"
+        )),
+        None => Ok(format!("{}.{}", input, ext)),
+    }
+}
+
+pub(super) fn write_key_pair(
+    secret_path: &str,
+    secret: &str,
+    public_path: &str,
+    public: &str,
+    force: bool,
+) -> anyhow::Result<()> {
+    for path in [secret_path, public_path] {
+        if !force && Path::new(path).exists() {
+            return Err(anyhow::anyhow!(
+                "{} already exists, use --force to overwrite",
+                path
+            ));
+        }
+    }
+    write_private(secret_path, secret.as_bytes())?;
+    fs::write(public_path, public)?;
+    Ok(())
+}
@@ -1,8 +1,18 @@
+mod age;
 mod base64;
+mod codec;
+mod compress;
 mod csv_opts;
+mod escape;
 mod genpass;
+mod hexdump;
 mod http;
+mod inspect;
 mod jwt;
+mod minisign;
+mod num;
+mod pass;
+mod signify;
 mod text;
 
 use std::path::{Path, PathBuf};
@@ -10,7 +20,10 @@ use std::path::{Path, PathBuf};
 use clap::Parser;
 use enum_dispatch::enum_dispatch;
 
-pub use self::{base64::*, csv_opts::*, genpass::*, http::*, jwt::*, text::*};
+pub use self::{
+    age::*, base64::*, codec::*, compress::*, csv_opts::*, escape::*, genpass::*, hexdump::*,
+    http::*, inspect::*, jwt::*, minisign::*, num::*, pass::*, signify::*, text::*,
+};
 
 /// Simple program to deal with csv
 #[derive(Debug, Parser)]
@@ -32,11 +45,53 @@ pub enum SubCommand {
 
     #[command(subcommand, about = "Base64 encode/decode")]
     Base64(Base64SubCommand),
+    #[command(subcommand, about = "Hex/base32/base58/base85 encode/decode")]
+    Codec(CodecSubCommand),
+    #[command(
+        name = "compress",
+        about = "Compress a file with gzip/zlib/deflate/zstd/brotli/xz"
+    )]
+    Compress(CompressOpts),
+    #[command(
+        name = "decompress",
+        about = "Decompress a gzip/zlib/deflate/zstd/brotli/xz file"
+    )]
+    Decompress(DecompressOpts),
+    #[command(subcommand, about = "URL percent-encode/decode")]
+    Url(UrlSubCommand),
+    #[command(subcommand, about = "HTML entity escape/unescape")]
+    Html(HtmlSubCommand),
+    #[command(subcommand, about = "JSON/Rust unicode escape/unescape")]
+    Unicode(UnicodeSubCommand),
     #[command(subcommand, about = "Text sign/verify")]
     Text(TextSubCommand),
+    #[command(subcommand, about = "age-compatible encryption to X25519 recipients")]
+    Age(AgeSubCommand),
+    #[command(subcommand, about = "minisign-compatible keys and signatures")]
+    Minisign(MinisignSubCommand),
+    #[command(subcommand, about = "OpenBSD signify-compatible keys and signatures")]
+    Signify(SignifySubCommand),
     #[command(subcommand, about = "HTTP server")]
     Http(HttpSubCommand),
 
+    #[command(
+        name = "inspect",
+        about = "Guess the encoding of a blob and decode it layer by layer"
+    )]
+    Inspect(InspectOpts),
+
+    #[command(
+        name = "hexdump",
+        about = "Dump a file as xxd-style hex, or turn a hexdump back into binary"
+    )]
+    Hexdump(HexdumpOpts),
+
+    #[command(subcommand, about = "Number base, byte size and duration conversion")]
+    Num(NumSubCommand),
+
+    #[command(subcommand, about = "Password hash/verify")]
+    Pass(PassSubCommand),
+
     #[command(name = "genjwt", about = "generate a jwt token")]
     GenJwt(GenJwt),
     #[command(name = "verifyjwt", about = "validate a jwt token")]
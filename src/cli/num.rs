@@ -0,0 +1,83 @@
+use clap::Parser;
+use enum_dispatch::enum_dispatch;
+
+use crate::{process_duration, process_radix, process_size, CmdExector};
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum NumSubCommand {
+    #[command(
+        name = "radix",
+        about = "Convert an integer between dec/hex/oct/bin or any radix"
+    )]
+    Radix(NumRadixOpts),
+
+    #[command(name = "size", about = "Convert byte sizes between SI and IEC units")]
+    Size(NumSizeOpts),
+
+    #[command(name = "duration", about = "Convert durations like 1h30m")]
+    Duration(NumDurationOpts),
+}
+
+#[derive(Debug, Parser)]
+pub struct NumRadixOpts {
+    /// the number, 0x/0o/0b prefixes are detected when --from is not given
+    #[arg(allow_hyphen_values = true)]
+    pub value: String,
+
+    /// radix of the input, 2-36
+    #[arg(long, value_parser = clap::value_parser!(u32).range(2..=36))]
+    pub from: Option<u32>,
+
+    /// only print the number in this radix, 2-36
+    #[arg(long, value_parser = clap::value_parser!(u32).range(2..=36))]
+    pub to: Option<u32>,
+
+    /// bit width for two's complement
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub width: Option<u32>,
+}
+
+#[derive(Debug, Parser)]
+pub struct NumSizeOpts {
+    /// size with an optional unit, e.g. 1.5GiB or "300 MB"
+    pub value: String,
+
+    /// only print the size in this unit, e.g. KiB or MB
+    #[arg(long)]
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct NumDurationOpts {
+    /// duration such as 1h30m, 1.5d or 250ms, a bare number is in seconds
+    pub value: String,
+
+    /// only print the duration in this unit: w, d, h, m, s, ms, us or ns
+    #[arg(long)]
+    pub to: Option<String>,
+}
+
+impl CmdExector for NumRadixOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let result = process_radix(&self.value, self.from, self.to, self.width)?;
+        println!("{}", result);
+        Ok(())
+    }
+}
+
+impl CmdExector for NumSizeOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let result = process_size(&self.value, self.to.as_deref())?;
+        println!("{}", result);
+        Ok(())
+    }
+}
+
+impl CmdExector for NumDurationOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let result = process_duration(&self.value, self.to.as_deref())?;
+        println!("{}", result);
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    fmt,
+    io::{BufRead, BufReader},
+    str::FromStr,
+};
+
+use clap::{Args, Parser};
+use enum_dispatch::enum_dispatch;
+
+use crate::{
+    get_reader, process_genpass, process_pass_breached, process_pass_hash, process_pass_verify,
+    read_secret, CmdExector,
+};
+
+use super::verify_file;
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum PassSubCommand {
+    #[command(about = "Hash a password into a PHC string")]
+    Hash(PassHashOpts),
+
+    #[command(about = "Verify a password against a PHC or bcrypt hash")]
+    Verify(PassVerifyOpts),
+
+    #[command(about = "Check passwords against a local breached-password hash list")]
+    Breached(PassBreachedOpts),
+}
+
+#[derive(Debug, Parser)]
+pub struct PassHashOpts {
+    #[arg(long, default_value = "argon2id", value_parser = parse_hash_algorithm)]
+    pub algo: PassHashAlgorithm,
+
+    #[command(flatten)]
+    pub params: PassHashParams,
+}
+
+/// Cost parameters, unset ones fall back to the algorithm's recommended value
+#[derive(Debug, Clone, Default, Args)]
+pub struct PassHashParams {
+    /// argon2 memory cost in KiB
+    #[arg(long)]
+    pub m_cost: Option<u32>,
+    /// argon2 iterations
+    #[arg(long)]
+    pub t_cost: Option<u32>,
+    /// argon2 or scrypt parallelism
+    #[arg(long)]
+    pub p_cost: Option<u32>,
+    /// bcrypt cost (4-31)
+    #[arg(long)]
+    pub cost: Option<u32>,
+    /// scrypt log2(N)
+    #[arg(long)]
+    pub log_n: Option<u8>,
+    /// scrypt block size
+    #[arg(long)]
+    pub block_size: Option<u32>,
+    /// pbkdf2 rounds
+    #[arg(long)]
+    pub rounds: Option<u32>,
+}
+
+#[derive(Debug, Parser)]
+pub struct PassVerifyOpts {
+    #[arg(long)]
+    pub hash: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct PassBreachedOpts {
+    /// sorted SHA-1 hash file, or a directory of 5-hex-digit range files
+    #[arg(long, value_parser = verify_file)]
+    pub db: String,
+    /// file with one candidate password per line
+    #[arg(short, long, value_parser = verify_file)]
+    pub input: Option<String>,
+    /// check this many newly generated passwords instead
+    #[arg(long, conflicts_with = "input")]
+    pub generate: Option<u32>,
+    #[arg(short, long, default_value_t = 16)]
+    pub length: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassHashAlgorithm {
+    Argon2id,
+    Bcrypt,
+    Scrypt,
+    Pbkdf2Sha256,
+    Pbkdf2Sha512,
+}
+
+fn parse_hash_algorithm(algo: &str) -> Result<PassHashAlgorithm, anyhow::Error> {
+    algo.parse()
+}
+
+impl FromStr for PassHashAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "argon2id" => Ok(PassHashAlgorithm::Argon2id),
+            "bcrypt" => Ok(PassHashAlgorithm::Bcrypt),
+            "scrypt" => Ok(PassHashAlgorithm::Scrypt),
+            "pbkdf2" | "pbkdf2-sha256" => Ok(PassHashAlgorithm::Pbkdf2Sha256),
+            "pbkdf2-sha512" => Ok(PassHashAlgorithm::Pbkdf2Sha512),
+            _ => Err(anyhow::anyhow!("Invalid algorithm: {}", s)),
+        }
+    }
+}
+
+impl From<PassHashAlgorithm> for &'static str {
+    fn from(value: PassHashAlgorithm) -> Self {
+        match value {
+            PassHashAlgorithm::Argon2id => "argon2id",
+            PassHashAlgorithm::Bcrypt => "bcrypt",
+            PassHashAlgorithm::Scrypt => "scrypt",
+            PassHashAlgorithm::Pbkdf2Sha256 => "pbkdf2-sha256",
+            PassHashAlgorithm::Pbkdf2Sha512 => "pbkdf2-sha512",
+        }
+    }
+}
+
+impl fmt::Display for PassHashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Into::<&str>::into(*self))
+    }
+}
+
+impl CmdExector for PassHashOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let password = read_secret("password: ", "RCLI_PASSWORD")?;
+        let hash = process_pass_hash(&password, self.algo, &self.params)?;
+        println!("{}", hash);
+        Ok(())
+    }
+}
+
+impl CmdExector for PassVerifyOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let password = read_secret("password: ", "RCLI_PASSWORD")?;
+        let valid = process_pass_verify(&password, &self.hash)?;
+        println!("verify: {}", valid);
+        Ok(())
+    }
+}
+
+impl CmdExector for PassBreachedOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        if let Some(n) = self.generate {
+            for _ in 0..n {
+                let password = process_genpass(self.length, true, true, true, true)?;
+                let count = process_pass_breached(&password, &self.db)?;
+                println!("{}: {}", password, count);
+            }
+        } else if let Some(input) = &self.input {
+            // 不回显用户提供的密码，只输出行号
+            let reader = BufReader::new(get_reader(input)?);
+            for (i, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.is_empty() {
+                    continue;
+                }
+                let count = process_pass_breached(&line, &self.db)?;
+                println!("line {}: {}", i + 1, count);
+            }
+        } else {
+            let password = read_secret("password: ", "RCLI_PASSWORD")?;
+            let count = process_pass_breached(&password, &self.db)?;
+            println!("breached: {}", count);
+        }
+        Ok(())
+    }
+}
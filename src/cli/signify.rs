@@ -0,0 +1,115 @@
+use std::{fs, path::Path};
+
+use clap::Parser;
+use enum_dispatch::enum_dispatch;
+
+use crate::{
+    get_reader, process_signify_keygen, process_signify_sign, process_signify_verify,
+    read_key_passphrase, CmdExector,
+};
+
+use super::{
+    minisign::{output_path, write_key_pair},
+    text::new_key_passphrase,
+    verify_file,
+};
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum SignifySubCommand {
+    #[command(about = "Generate a signify key pair")]
+    Keygen(SignifyKeygenOpts),
+
+    #[command(about = "Sign a file, writes <input>.sig")]
+    Sign(SignifySignOpts),
+
+    #[command(about = "Verify a signify signature")]
+    Verify(SignifyVerifyOpts),
+}
+
+#[derive(Debug, Parser)]
+pub struct SignifyKeygenOpts {
+    #[arg(short, long, default_value = "signify.sec")]
+    pub secret_key: String,
+    #[arg(short, long, default_value = "signify.pub")]
+    pub public_key: String,
+    /// written as "<comment> public key" in the untrusted comment
+    #[arg(short, long, default_value = "signify")]
+    pub comment: String,
+    /// encrypt the secret key (RCLI_KEY_PASSPHRASE_FILE, RCLI_KEY_PASSPHRASE or prompted)
+    #[arg(long)]
+    pub passphrase: bool,
+    /// overwrite existing key files
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct SignifySignOpts {
+    #[arg(short, long, value_parser = verify_file)]
+    pub input: String,
+    #[arg(short, long, value_parser = verify_file, default_value = "signify.sec")]
+    pub secret_key: String,
+    /// defaults to <input>.sig
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct SignifyVerifyOpts {
+    #[arg(short, long, value_parser = verify_file)]
+    pub input: String,
+    #[arg(short, long, value_parser = verify_file, required_unless_present = "public_key_string")]
+    pub public_key: Option<String>,
+    /// base64 public key
+    #[arg(short = 'P', long, conflicts_with = "public_key")]
+    pub public_key_string: Option<String>,
+    /// defaults to <input>.sig
+    #[arg(short = 'x', long, value_parser = verify_file)]
+    pub sig: Option<String>,
+}
+
+impl CmdExector for SignifyKeygenOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let passphrase = new_key_passphrase(self.passphrase)?;
+        let (sk, pk) = process_signify_keygen(passphrase.as_deref(), &self.comment)?;
+        write_key_pair(&self.secret_key, &sk, &self.public_key, &pk, self.force)?;
+        print!("{}", pk);
+        Ok(())
+    }
+}
+
+impl CmdExector for SignifySignOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let output = output_path(self.output, &self.input, "sig")?;
+        // 和 signify 一样，提示用同名的 .pub 验证
+        let name = Path::new(&self.secret_key)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let name = name.strip_suffix(".sec").unwrap_or(&name);
+        let comment = format!("verify with {}.pub", name);
+        let secret_key = fs::read_to_string(&self.secret_key)?;
+        let mut reader = get_reader(&self.input)?;
+        let sig = process_signify_sign(&mut reader, &secret_key, &read_key_passphrase, &comment)?;
+        fs::write(&output, sig)?;
+        eprintln!("signature written to {}", output);
+        Ok(())
+    }
+}
+
+impl CmdExector for SignifyVerifyOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let public_key = match (self.public_key, self.public_key_string) {
+            (_, Some(key)) => key,
+            (Some(path), None) => fs::read_to_string(path)?,
+            (None, None) => unreachable!("clap requires one of them"),
+        };
+        let sig = output_path(self.sig, &self.input, "sig")?;
+        let sig = fs::read_to_string(sig)?;
+        let mut reader = get_reader(&self.input)?;
+        let verify = process_signify_verify(&mut reader, &public_key, &sig)?;
+        println!("verify: {}", verify);
+        Ok(())
+    }
+}
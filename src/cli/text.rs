@@ -1,10 +1,16 @@
 use anyhow::Result;
 use enum_dispatch::enum_dispatch;
-use std::{fmt, fs, path::PathBuf, str::FromStr};
+use std::{fmt, fs, io::Write, path::PathBuf, str::FromStr};
 
 use clap::Parser;
 
-use crate::{process_generate, process_text_sign, process_text_verify, CmdExector};
+use crate::{
+    get_content, get_reader, get_writer, process_encode_generated, process_fingerprint,
+    process_generate, process_key_convert, process_manifest_build, process_manifest_check,
+    process_resolve_key, process_text_decrypt, process_text_encrypt, process_text_sign,
+    process_text_verify, read_key_passphrase, read_secret, write_private, CmdExector, CryptKey,
+    Key, Keyring, Manifest,
+};
 
 use super::{verify_file, verify_path};
 
@@ -19,28 +25,191 @@ pub enum TextSubCommand {
 
     #[command(about = "Generate a new key")]
     Generate(TextKeyGenerateOpts),
+
+    #[command(about = "Encrypt a file with a key file or passphrase")]
+    Encrypt(TextEncryptOpts),
+
+    #[command(about = "Decrypt a file encrypted by text encrypt")]
+    Decrypt(TextDecryptOpts),
+
+    #[command(subcommand, about = "Manage key files")]
+    Key(TextKeySubCommand),
+
+    #[command(subcommand, about = "Sign and verify a manifest of many files")]
+    Manifest(TextManifestSubCommand),
+
+    #[command(subcommand, about = "Manage named keys in the keyring (RCLI_KEYRING)")]
+    Keyring(TextKeyringSubCommand),
+}
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum TextKeySubCommand {
+    #[command(about = "Convert a key between raw, PEM, DER, OpenSSH and JWK")]
+    Convert(TextKeyConvertOpts),
+
+    #[command(about = "Show a key's fingerprint as hex, base64, words and randomart")]
+    Fingerprint(TextKeyFingerprintOpts),
+}
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum TextManifestSubCommand {
+    #[command(about = "Hash files and directories into a signed manifest")]
+    Sign(TextManifestSignOpts),
+
+    #[command(about = "Verify a signed manifest against the files on disk")]
+    Verify(TextManifestVerifyOpts),
+}
+
+#[derive(Debug, Parser)]
+#[enum_dispatch(CmdExector)]
+pub enum TextKeyringSubCommand {
+    #[command(about = "Generate a named key")]
+    Generate(TextKeyringGenerateOpts),
+
+    #[command(about = "Import a key file under a name")]
+    Import(TextKeyringImportOpts),
+
+    #[command(about = "Export a key as raw, PEM, DER, OpenSSH or JWK")]
+    Export(TextKeyringExportOpts),
+
+    #[command(about = "List keys with their algorithm and fingerprint")]
+    List(TextKeyringListOpts),
+
+    #[command(about = "Delete a key by name or fingerprint")]
+    Delete(TextKeyringDeleteOpts),
 }
 
 #[derive(Debug, Parser)]
 pub struct TextSignOpts {
     #[arg(short, long, value_parser = verify_file, default_value = "-")]
     pub input: String,
-    #[arg(short, long, value_parser = verify_file)]
+    /// key file, or the name or fingerprint of a keyring key
+    #[arg(short, long)]
     pub key: String,
-    #[arg(long, default_value = "blake3", value_parser = parse_format)]
-    pub format: TextSignFormat,
+    /// defaults to the keyring key's algorithm, or blake3
+    #[arg(long, value_parser = parse_format)]
+    pub format: Option<TextSignFormat>,
+    /// write the signature to <input>.<sig-ext> instead of stdout
+    #[arg(long)]
+    pub detached: bool,
+    /// signature file extension, pick another one (e.g. rsig) if signify's .sig files live next to the inputs
+    #[arg(long, default_value = "sig")]
+    pub sig_ext: String,
 }
 
 #[derive(Debug, Parser)]
 pub struct TextVerifyOpts {
     #[arg(short, long, value_parser = verify_file, default_value = "-")]
     pub input: String,
-    #[arg(short, long, value_parser = verify_file)]
+    /// key file, or the name or fingerprint of a keyring key
+    #[arg(short, long)]
     pub key: String,
+    /// defaults to the keyring key's algorithm, or blake3
+    #[arg(long, value_parser = parse_format)]
+    pub format: Option<TextSignFormat>,
+    /// signature, <input>.<sig-ext> is read when neither --sig nor --sig-file is given
     #[arg(long)]
-    pub sig: String,
-    #[arg(long, default_value = "blake3", value_parser = parse_format)]
+    pub sig: Option<String>,
+    #[arg(long, value_parser = verify_file, conflicts_with = "sig")]
+    pub sig_file: Option<String>,
+    /// signature file extension, pick another one (e.g. rsig) if signify's .sig files live next to the inputs
+    #[arg(long, default_value = "sig")]
+    pub sig_ext: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct TextManifestSignOpts {
+    /// files or directories to include, relative to --root
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+    /// key file, or the name or fingerprint of a keyring key
+    #[arg(short, long)]
+    pub key: String,
+    /// defaults to the keyring key's algorithm, or blake3
+    #[arg(long, value_parser = parse_format)]
+    pub format: Option<TextSignFormat>,
+    /// manifest file, the signature goes to <output>.<sig-ext>
+    #[arg(short, long, default_value = "MANIFEST")]
+    pub output: String,
+    #[arg(long, value_parser = verify_path, default_value = ".")]
+    pub root: PathBuf,
+    /// signature file extension, pick another one (e.g. rsig) if signify's .sig files live next to the inputs
+    #[arg(long, default_value = "sig")]
+    pub sig_ext: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct TextManifestVerifyOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "MANIFEST")]
+    pub manifest: String,
+    /// key file, or the name or fingerprint of a keyring key
+    #[arg(short, long)]
+    pub key: String,
+    /// defaults to the keyring key's algorithm, or blake3
+    #[arg(long, value_parser = parse_format)]
+    pub format: Option<TextSignFormat>,
+    /// defaults to <manifest>.<sig-ext>
+    #[arg(long, value_parser = verify_file)]
+    pub sig_file: Option<String>,
+    #[arg(long, value_parser = verify_path, default_value = ".")]
+    pub root: PathBuf,
+    /// signature file extension, pick another one (e.g. rsig) if signify's .sig files live next to the inputs
+    #[arg(long, default_value = "sig")]
+    pub sig_ext: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct TextKeyringGenerateOpts {
+    pub name: String,
+    #[arg(short, long, default_value = "ed25519", value_parser = parse_format)]
     pub format: TextSignFormat,
+    #[arg(short, long, default_value = "")]
+    pub comment: String,
+    /// encrypt the private key (RCLI_KEY_PASSPHRASE_FILE, RCLI_KEY_PASSPHRASE or prompted)
+    #[arg(long)]
+    pub passphrase: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct TextKeyringImportOpts {
+    pub name: String,
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+    /// algorithm, detected from the key unless it is raw or symmetric
+    #[arg(short, long, value_parser = parse_format)]
+    pub format: Option<TextSignFormat>,
+    /// the raw input key is a public key
+    #[arg(long, requires = "format")]
+    pub raw_public: bool,
+    #[arg(short, long, default_value = "")]
+    pub comment: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct TextKeyringExportOpts {
+    /// name or fingerprint
+    pub name: String,
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+    #[arg(long, default_value = "pem", value_parser = parse_key_format)]
+    pub to: KeyFormat,
+    /// only export the public half
+    #[arg(long)]
+    pub public: bool,
+    /// encrypt the exported private key
+    #[arg(long, conflicts_with = "public")]
+    pub passphrase: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct TextKeyringListOpts {}
+
+#[derive(Debug, Parser)]
+pub struct TextKeyringDeleteOpts {
+    /// name or fingerprint
+    pub name: String,
 }
 
 #[derive(Debug, Parser)]
@@ -49,18 +218,141 @@ pub struct TextKeyGenerateOpts {
     pub format: TextSignFormat,
     #[arg(short, long, value_parser = verify_path)]
     pub output: PathBuf,
+    /// pem by default, symmetric keys are always raw unless jwk is asked for
+    #[arg(long, value_parser = parse_key_format)]
+    pub key_format: Option<KeyFormat>,
+    /// encrypt the private key (RCLI_KEY_PASSPHRASE_FILE, RCLI_KEY_PASSPHRASE or prompted)
+    #[arg(long)]
+    pub passphrase: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct TextKeyFingerprintOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+    #[arg(long, default_value = "sha256", value_parser = parse_fingerprint_hash)]
+    pub hash: FingerprintHash,
+    /// only print one form: hex, base64, words or randomart
+    #[arg(long, value_parser = parse_fingerprint_form)]
+    pub form: Option<FingerprintForm>,
+    /// algorithm of a raw input key, other formats are detected
+    #[arg(long, value_parser = parse_format)]
+    pub format: Option<TextSignFormat>,
+    /// the raw input key is a public key
+    #[arg(long, requires = "format")]
+    pub raw_public: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct TextKeyConvertOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+    #[arg(long, value_parser = parse_key_format)]
+    pub to: KeyFormat,
+    /// only write the public half of a private key
+    #[arg(long)]
+    pub public: bool,
+    /// algorithm of a raw input key, other formats are detected
+    #[arg(long, value_parser = parse_format)]
+    pub format: Option<TextSignFormat>,
+    /// the raw input key is a public key
+    #[arg(long, requires = "format")]
+    pub raw_public: bool,
+    /// encrypt the output private key, encrypted input keys are always detected
+    #[arg(long, conflicts_with = "public")]
+    pub passphrase: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct TextEncryptOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+    /// 32-byte key file, e.g. from `text generate --format blake3`
+    #[arg(short, long, value_parser = verify_file, required_unless_present = "passphrase")]
+    pub key: Option<String>,
+    /// derive the key from a passphrase (prompted, or RCLI_PASSPHRASE)
+    #[arg(long, conflicts_with = "key")]
+    pub passphrase: bool,
+    #[arg(long, default_value = "chacha20-poly1305", value_parser = parse_encrypt_format)]
+    pub format: TextEncryptFormat,
+}
+
+#[derive(Debug, Parser)]
+pub struct TextDecryptOpts {
+    #[arg(short, long, value_parser = verify_file, default_value = "-")]
+    pub input: String,
+    #[arg(short, long, default_value = "-")]
+    pub output: String,
+    #[arg(short, long, value_parser = verify_file, required_unless_present = "passphrase")]
+    pub key: Option<String>,
+    #[arg(long, conflicts_with = "key")]
+    pub passphrase: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum TextSignFormat {
     Blake3,
     Ed25519,
+    Ed25519ph,
+    HmacSha256,
+    EcdsaP256,
+    EcdsaP384,
+    RsaPss,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    Raw,
+    Pem,
+    Der,
+    OpenSsh,
+    Jwk,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FingerprintHash {
+    Sha256,
+    Blake3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FingerprintForm {
+    Hex,
+    Base64,
+    Words,
+    Randomart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncryptFormat {
+    ChaCha20Poly1305,
+    Aes256Gcm,
 }
 
 fn parse_format(format: &str) -> Result<TextSignFormat, anyhow::Error> {
     format.parse()
 }
 
+fn parse_key_format(format: &str) -> Result<KeyFormat, anyhow::Error> {
+    format.parse()
+}
+
+fn parse_fingerprint_hash(hash: &str) -> Result<FingerprintHash, anyhow::Error> {
+    hash.parse()
+}
+
+fn parse_fingerprint_form(form: &str) -> Result<FingerprintForm, anyhow::Error> {
+    form.parse()
+}
+
+fn parse_encrypt_format(format: &str) -> Result<TextEncryptFormat, anyhow::Error> {
+    format.parse()
+}
+
 impl FromStr for TextSignFormat {
     type Err = anyhow::Error;
 
@@ -68,6 +360,11 @@ impl FromStr for TextSignFormat {
         match s {
             "blake3" => Ok(TextSignFormat::Blake3),
             "ed25519" => Ok(TextSignFormat::Ed25519),
+            "ed25519ph" => Ok(TextSignFormat::Ed25519ph),
+            "hmac-sha256" | "hmac" => Ok(TextSignFormat::HmacSha256),
+            "ecdsa-p256" | "p256" => Ok(TextSignFormat::EcdsaP256),
+            "ecdsa-p384" | "p384" => Ok(TextSignFormat::EcdsaP384),
+            "rsa-pss" => Ok(TextSignFormat::RsaPss),
             _ => Err(anyhow::anyhow!("Invalid format: {}", s)),
         }
     }
@@ -78,6 +375,11 @@ impl From<TextSignFormat> for &'static str {
         match value {
             TextSignFormat::Blake3 => "blake3",
             TextSignFormat::Ed25519 => "ed25519",
+            TextSignFormat::Ed25519ph => "ed25519ph",
+            TextSignFormat::HmacSha256 => "hmac-sha256",
+            TextSignFormat::EcdsaP256 => "ecdsa-p256",
+            TextSignFormat::EcdsaP384 => "ecdsa-p384",
+            TextSignFormat::RsaPss => "rsa-pss",
         }
     }
 }
@@ -88,34 +390,259 @@ impl std::fmt::Display for TextSignFormat {
     }
 }
 
+impl FromStr for KeyFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "raw" => Ok(KeyFormat::Raw),
+            "pem" => Ok(KeyFormat::Pem),
+            "der" => Ok(KeyFormat::Der),
+            "openssh" | "ssh" => Ok(KeyFormat::OpenSsh),
+            "jwk" => Ok(KeyFormat::Jwk),
+            _ => Err(anyhow::anyhow!("Invalid key format: {}", s)),
+        }
+    }
+}
+
+impl From<KeyFormat> for &'static str {
+    fn from(value: KeyFormat) -> Self {
+        match value {
+            KeyFormat::Raw => "raw",
+            KeyFormat::Pem => "pem",
+            KeyFormat::Der => "der",
+            KeyFormat::OpenSsh => "openssh",
+            KeyFormat::Jwk => "jwk",
+        }
+    }
+}
+
+impl fmt::Display for KeyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Into::<&str>::into(*self))
+    }
+}
+
+impl FromStr for FingerprintHash {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sha256" => Ok(FingerprintHash::Sha256),
+            "blake3" => Ok(FingerprintHash::Blake3),
+            _ => Err(anyhow::anyhow!("Invalid fingerprint hash: {}", s)),
+        }
+    }
+}
+
+impl From<FingerprintHash> for &'static str {
+    fn from(value: FingerprintHash) -> Self {
+        match value {
+            FingerprintHash::Sha256 => "sha256",
+            FingerprintHash::Blake3 => "blake3",
+        }
+    }
+}
+
+impl fmt::Display for FingerprintHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Into::<&str>::into(*self))
+    }
+}
+
+impl FromStr for FingerprintForm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hex" => Ok(FingerprintForm::Hex),
+            "base64" => Ok(FingerprintForm::Base64),
+            "words" => Ok(FingerprintForm::Words),
+            "randomart" | "art" => Ok(FingerprintForm::Randomart),
+            _ => Err(anyhow::anyhow!("Invalid fingerprint form: {}", s)),
+        }
+    }
+}
+
+impl From<FingerprintForm> for &'static str {
+    fn from(value: FingerprintForm) -> Self {
+        match value {
+            FingerprintForm::Hex => "hex",
+            FingerprintForm::Base64 => "base64",
+            FingerprintForm::Words => "words",
+            FingerprintForm::Randomart => "randomart",
+        }
+    }
+}
+
+impl fmt::Display for FingerprintForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Into::<&str>::into(*self))
+    }
+}
+
+impl FromStr for TextEncryptFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "chacha20-poly1305" | "chacha20" => Ok(TextEncryptFormat::ChaCha20Poly1305),
+            "aes-256-gcm" | "aes" => Ok(TextEncryptFormat::Aes256Gcm),
+            _ => Err(anyhow::anyhow!("Invalid format: {}", s)),
+        }
+    }
+}
+
+impl From<TextEncryptFormat> for &'static str {
+    fn from(value: TextEncryptFormat) -> Self {
+        match value {
+            TextEncryptFormat::ChaCha20Poly1305 => "chacha20-poly1305",
+            TextEncryptFormat::Aes256Gcm => "aes-256-gcm",
+        }
+    }
+}
+
+impl fmt::Display for TextEncryptFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Into::<&str>::into(*self))
+    }
+}
+
 impl CmdExector for TextSignOpts {
     async fn execute(self) -> anyhow::Result<()> {
-        let sign = process_text_sign(&self.input, &self.key, self.format)?;
-        println!("sign: {}", sign);
+        let (key, format) = process_resolve_key(&self.key, self.format)?;
+        let sign = process_text_sign(&self.input, &key, format)?;
+        if self.detached {
+            if self.input == "-" {
+                return Err(anyhow::anyhow!("--detached needs a file input"));
+            }
+            let path = sig_path(&self.input, &self.sig_ext);
+            // 不要覆盖旁边 signify/minisign 的签名
+            if is_signify_sig(&path) {
+                return Err(anyhow::anyhow!(
+                    "{} is a signify/minisign signature, pick another --sig-ext",
+                    path
+                ));
+            }
+            fs::write(&path, format!("{}\n", sign))?;
+            eprintln!("signature written to {}", path);
+        } else {
+            println!("sign: {}", sign);
+        }
         Ok(())
     }
 }
 
 impl CmdExector for TextVerifyOpts {
     async fn execute(self) -> anyhow::Result<()> {
-        let verify = process_text_verify(&self.input, &self.key, &self.sig, self.format)?;
+        let sig = match (self.sig, self.sig_file) {
+            (Some(sig), _) => sig,
+            (None, Some(path)) => read_sig(&path)?,
+            (None, None) if self.input == "-" => {
+                return Err(anyhow::anyhow!("--sig or --sig-file is required for stdin"));
+            }
+            (None, None) => read_sig(&sig_path(&self.input, &self.sig_ext))?,
+        };
+        let (key, format) = process_resolve_key(&self.key, self.format)?;
+        let verify = process_text_verify(&self.input, &key, &sig, format)?;
         println!("verify: {}", verify);
         Ok(())
     }
 }
 
+impl CmdExector for TextManifestSignOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let (key, format) = process_resolve_key(&self.key, self.format)?;
+        let sig = sig_path(&self.output, &self.sig_ext);
+        let skip = [PathBuf::from(&self.output), PathBuf::from(&sig)];
+        let manifest = process_manifest_build(&self.root, &self.paths, &skip)?;
+        fs::write(&self.output, manifest.to_string())?;
+        let sign = process_text_sign(&self.output, &key, format)?;
+        fs::write(&sig, format!("{}\n", sign))?;
+        eprintln!(
+            "{} files written to {}, signature written to {}",
+            manifest.entries.len(),
+            self.output,
+            sig
+        );
+        Ok(())
+    }
+}
+
+impl CmdExector for TextManifestVerifyOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let sig_file = self
+            .sig_file
+            .unwrap_or_else(|| sig_path(&self.manifest, &self.sig_ext));
+        let sig = read_sig(&sig_file)?;
+        let (key, format) = process_resolve_key(&self.key, self.format)?;
+        // 先验签，签名不对就不用再看文件了
+        if !process_text_verify(&self.manifest, &key, &sig, format)? {
+            println!("verify: false");
+            return Ok(());
+        }
+        let manifest: Manifest = fs::read_to_string(&self.manifest)?.parse()?;
+        let skip = [PathBuf::from(&self.manifest), PathBuf::from(&sig_file)];
+        let report = process_manifest_check(&self.root, &manifest, &skip)?;
+        for path in &report.tampered {
+            println!("tampered: {}", path);
+        }
+        for path in &report.missing {
+            println!("missing: {}", path);
+        }
+        for path in &report.extra {
+            println!("extra: {}", path);
+        }
+        println!("verify: {}", report.is_ok());
+        Ok(())
+    }
+}
+
+fn sig_path(input: &str, ext: &str) -> String {
+    format!("{}.{}", input, ext.trim_start_matches('.'))
+}
+
+fn read_sig(path: &str) -> Result<String> {
+    if is_signify_sig(path) {
+        return Err(anyhow::anyhow!(
+            "{} is a signify/minisign signature, use rcli signify verify or rcli minisign verify",
+            path
+        ));
+    }
+    Ok(fs::read_to_string(path)?.trim().to_string())
+}
+
+fn is_signify_sig(path: &str) -> bool {
+    fs::read_to_string(path).is_ok_and(|sig| sig.starts_with("untrusted comment:"))
+}
+
 impl CmdExector for TextKeyGenerateOpts {
     async fn execute(self) -> anyhow::Result<()> {
+        let key_format = self.key_format.unwrap_or(match self.format {
+            TextSignFormat::Blake3 | TextSignFormat::HmacSha256 => KeyFormat::Raw,
+            _ => KeyFormat::Pem,
+        });
+        let passphrase = new_key_passphrase(self.passphrase)?;
         let key = process_generate(self.format)?;
+        let key = process_encode_generated(self.format, key, key_format, passphrase.as_deref())?;
         match self.format {
-            TextSignFormat::Ed25519 => {
+            TextSignFormat::Ed25519 | TextSignFormat::Ed25519ph => {
                 let name = &self.output;
-                fs::write(name.join("ed25519.sk"), &key[0])?;
+                write_private(name.join("ed25519.sk"), &key[0])?;
                 fs::write(name.join("ed25519.pk"), &key[1])?;
             }
             TextSignFormat::Blake3 => {
                 let name = self.output.join("blake3.txt");
-                fs::write(name, &key[0])?;
+                write_private(name, &key[0])?;
+            }
+            TextSignFormat::HmacSha256 => {
+                let name = self.output.join("hmac-sha256.txt");
+                write_private(name, &key[0])?;
+            }
+            TextSignFormat::EcdsaP256 | TextSignFormat::EcdsaP384 | TextSignFormat::RsaPss => {
+                let name = &self.output;
+                write_private(name.join(format!("{}.sk", self.format)), &key[0])?;
+                fs::write(name.join(format!("{}.pk", self.format)), &key[1])?;
             }
         }
         println!("{:?}", self);
@@ -123,6 +650,153 @@ impl CmdExector for TextKeyGenerateOpts {
     }
 }
 
+impl CmdExector for TextKeyConvertOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let data = get_content(&self.input)?;
+        let passphrase = new_key_passphrase(self.passphrase)?;
+        let key = process_key_convert(
+            &data,
+            self.to,
+            self.public,
+            self.format,
+            self.raw_public,
+            passphrase.as_deref(),
+        )?;
+        let mut writer = get_writer(&self.output)?;
+        writer.write_all(&key)?;
+        Ok(())
+    }
+}
+
+impl CmdExector for TextKeyFingerprintOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let data = get_content(&self.input)?;
+        let key = Key::decode(&data, self.format, !self.raw_public)?;
+        let fingerprint = process_fingerprint(&key, self.hash)?;
+        match self.form {
+            Some(FingerprintForm::Hex) => println!("{}", fingerprint.hex()),
+            Some(FingerprintForm::Base64) => println!("{}", fingerprint.base64()),
+            Some(FingerprintForm::Words) => println!("{}", fingerprint.words()),
+            Some(FingerprintForm::Randomart) => print!("{}", fingerprint.randomart()),
+            None => {
+                println!("{}", fingerprint.base64());
+                println!("hex: {}", fingerprint.hex());
+                println!("words: {}", fingerprint.words());
+                print!("{}", fingerprint.randomart());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CmdExector for TextKeyringGenerateOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let passphrase = new_key_passphrase(self.passphrase)?;
+        let entry = Keyring::open()?.generate(
+            &self.name,
+            self.format,
+            &self.comment,
+            passphrase.as_deref(),
+        )?;
+        println!("{} {} {}", entry.name, entry.algorithm, entry.fingerprint);
+        Ok(())
+    }
+}
+
+impl CmdExector for TextKeyringImportOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let data = get_content(&self.input)?;
+        let entry = Keyring::open()?.import(
+            &self.name,
+            &data,
+            self.format,
+            self.raw_public,
+            &self.comment,
+        )?;
+        println!("{} {} {}", entry.name, entry.algorithm, entry.fingerprint);
+        Ok(())
+    }
+}
+
+impl CmdExector for TextKeyringExportOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let passphrase = new_key_passphrase(self.passphrase)?;
+        let key =
+            Keyring::open()?.export(&self.name, self.to, self.public, passphrase.as_deref())?;
+        let mut writer = get_writer(&self.output)?;
+        writer.write_all(&key)?;
+        Ok(())
+    }
+}
+
+impl CmdExector for TextKeyringListOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        for entry in Keyring::open()?.list()? {
+            let kind = if entry.private { "private" } else { "public" };
+            println!(
+                "{:<16} {:<12} {:<8} {}  {}  {}",
+                entry.name,
+                entry.algorithm,
+                kind,
+                entry.created_at,
+                // 元数据可能被手改过，不能直接切片
+                entry.fingerprint.get(..16).unwrap_or(&entry.fingerprint),
+                entry.comment
+            );
+        }
+        Ok(())
+    }
+}
+
+impl CmdExector for TextKeyringDeleteOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let entry = Keyring::open()?.delete(&self.name)?;
+        eprintln!("deleted {} ({})", entry.name, entry.fingerprint);
+        Ok(())
+    }
+}
+
+impl CmdExector for TextEncryptOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let key = crypt_key(self.key.as_deref())?;
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_text_encrypt(&mut reader, &mut writer, &key, self.format)?;
+        Ok(())
+    }
+}
+
+impl CmdExector for TextDecryptOpts {
+    async fn execute(self) -> anyhow::Result<()> {
+        let key = crypt_key(self.key.as_deref())?;
+        let mut reader = get_reader(&self.input)?;
+        let mut writer = get_writer(&self.output)?;
+        process_text_decrypt(&mut reader, &mut writer, &key)?;
+        Ok(())
+    }
+}
+
+pub(super) fn new_key_passphrase(enabled: bool) -> Result<Option<String>> {
+    if !enabled {
+        return Ok(None);
+    }
+    let passphrase = read_key_passphrase()?;
+    if passphrase.is_empty() {
+        return Err(anyhow::anyhow!("key passphrase must not be empty"));
+    }
+    Ok(Some(passphrase))
+}
+
+fn crypt_key(key: Option<&str>) -> Result<CryptKey> {
+    match key {
+        Some(path) => CryptKey::load(path),
+        None => Ok(CryptKey::Passphrase(read_secret(
+            "passphrase: ",
+            "RCLI_PASSPHRASE",
+        )?)),
+    }
+}
+
 // impl CmdExector for TextSubCommand {
 //     async fn execute(self) -> anyhow::Result<()> {
 //         match self {
@@ -0,0 +1,166 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    iter,
+    path::Path,
+};
+
+use age::{
+    armor::{ArmoredReader, ArmoredWriter, Format},
+    scrypt,
+    secrecy::{ExposeSecret, SecretString},
+    x25519, Decryptor, Encryptor, Identity, Recipient,
+};
+use anyhow::Result;
+
+/// Generate an X25519 identity, returns the identity file content and the recipient
+pub fn process_age_keygen() -> (String, String) {
+    let identity = x25519::Identity::generate();
+    let recipient = identity.to_public().to_string();
+    // 和 age-keygen 的输出格式一致
+    let content = format!(
+        "# public key: {}\n{}\n",
+        recipient,
+        identity.to_string().expose_secret()
+    );
+    (content, recipient)
+}
+
+/// Read the keys from an age identity or recipients file, skipping comments and blank lines
+pub fn load_age_keys(path: impl AsRef<Path>) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+/// Encrypt to every `age1...` recipient, or to a passphrase with an scrypt stanza
+pub fn process_age_encrypt(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    recipients: &[String],
+    passphrase: Option<String>,
+    armor: bool,
+) -> Result<()> {
+    let encryptor = match passphrase {
+        Some(passphrase) => Encryptor::with_user_passphrase(SecretString::from(passphrase)),
+        None => {
+            let recipients = recipients
+                .iter()
+                .map(|r| {
+                    r.parse::<x25519::Recipient>()
+                        .map_err(|e| anyhow::anyhow!("invalid recipient {}: {}", r, e))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Encryptor::with_recipients(recipients.iter().map(|r| r as &dyn Recipient))?
+        }
+    };
+    let format = if armor {
+        Format::AsciiArmor
+    } else {
+        Format::Binary
+    };
+    let armored = ArmoredWriter::wrap_output(writer, format)?;
+    let mut stream = encryptor.wrap_output(armored)?;
+    std::io::copy(reader, &mut stream)?;
+    // 两层都要 finish，否则最后一块和 armor 的结尾不会写出去
+    stream.finish()?.finish()?.flush()?;
+    Ok(())
+}
+
+/// Decrypt a binary or armored age file with X25519 identities or a passphrase
+pub fn process_age_decrypt(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    identities: &[String],
+    passphrase: Option<String>,
+) -> Result<()> {
+    let decryptor = Decryptor::new(ArmoredReader::new(reader))?;
+    let mut decrypted = match passphrase {
+        Some(passphrase) => {
+            if !decryptor.is_scrypt() {
+                return Err(anyhow::anyhow!(
+                    "file is encrypted to recipients, not a passphrase"
+                ));
+            }
+            let identity = scrypt::Identity::new(SecretString::from(passphrase));
+            decryptor.decrypt(iter::once(&identity as &dyn Identity))?
+        }
+        None => {
+            if decryptor.is_scrypt() {
+                return Err(anyhow::anyhow!("file is encrypted with a passphrase"));
+            }
+            let identities = identities
+                .iter()
+                .map(|i| {
+                    i.parse::<x25519::Identity>()
+                        .map_err(|_| anyhow::anyhow!("invalid age identity"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            decryptor.decrypt(identities.iter().map(|i| i as &dyn Identity))?
+        }
+    };
+    std::io::copy(&mut decrypted, writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_age_multi_recipient_armor() -> Result<()> {
+        let (alice, alice_pk) = process_age_keygen();
+        let (bob, bob_pk) = process_age_keygen();
+        let alice = alice.lines().last().unwrap().to_string();
+        let bob = bob.lines().last().unwrap().to_string();
+        assert!(alice_pk.starts_with("age1") && alice.starts_with("AGE-SECRET-KEY-1"));
+
+        let mut encrypted = Vec::new();
+        let recipients = [alice_pk, bob_pk];
+        process_age_encrypt(
+            &mut &b"hello age"[..],
+            &mut encrypted,
+            &recipients,
+            None,
+            true,
+        )?;
+        assert!(encrypted.starts_with(b"-----BEGIN AGE ENCRYPTED FILE-----"));
+
+        for identity in [alice, bob] {
+            let mut decrypted = Vec::new();
+            process_age_decrypt(&mut &encrypted[..], &mut decrypted, &[identity], None)?;
+            assert_eq!(decrypted, b"hello age");
+        }
+        let (other, _) = process_age_keygen();
+        let other = other.lines().last().unwrap().to_string();
+        assert!(process_age_decrypt(&mut &encrypted[..], &mut Vec::new(), &[other], None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_age_passphrase() -> Result<()> {
+        let mut encrypted = Vec::new();
+        process_age_encrypt(
+            &mut &b"secret"[..],
+            &mut encrypted,
+            &[],
+            Some("hunter2".into()),
+            false,
+        )?;
+        assert!(encrypted.starts_with(b"age-encryption.org/v1\n-> scrypt "));
+        let mut decrypted = Vec::new();
+        process_age_decrypt(
+            &mut &encrypted[..],
+            &mut decrypted,
+            &[],
+            Some("hunter2".into()),
+        )?;
+        assert_eq!(decrypted, b"secret");
+        Ok(())
+    }
+}
@@ -1,60 +1,307 @@
-use std::io::Read;
+use std::io::{self, Read, Write};
 
-use crate::{get_reader, Base64Format};
+use crate::{Base64EncodeStyle, Base64Format};
 
 use base64::{
-    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
-    Engine as _,
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    read::DecoderReader,
+    write::EncoderWriter,
 };
 
-pub fn process_encode(input: &str, format: Base64Format) -> anyhow::Result<String> {
-    // input is a file
-    // if 会有一个返回类型，必须是同一个类型
-    // 找共同点 使用 Box 来包装提升类型
-    let mut reader = get_reader(input)?;
+// 每次读取固定大小的块，内存占用和输入大小无关
+const CHUNK_SIZE: usize = 64 * 1024;
 
-    let mut buf = Vec::new();
-    reader.read_to_end(&mut buf)?;
+// RFC 2045 规定 MIME 每行最多 76 个字符
+const MIME_LINE_WIDTH: usize = 76;
 
-    let encoded = match format {
-        Base64Format::Standard => STANDARD.encode(&buf),
-        Base64Format::UrlSafe => URL_SAFE_NO_PAD.encode(&buf),
+// 解码时不要求 padding，有没有都可以
+const DECODE_CONFIG: GeneralPurposeConfig =
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+
+static STANDARD_DECODER: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, DECODE_CONFIG);
+static URL_SAFE_DECODER: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, DECODE_CONFIG);
+// auto 会把 urlsafe 的字符映射成 standard 的字符
+static AUTO_DECODER: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    DECODE_CONFIG.with_decode_allow_trailing_bits(true),
+);
+
+pub fn process_encode(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    format: Base64Format,
+    style: Base64EncodeStyle,
+) -> anyhow::Result<()> {
+    let alphabet = match format {
+        Base64Format::Standard => &alphabet::STANDARD,
+        Base64Format::UrlSafe => &alphabet::URL_SAFE,
+        Base64Format::Auto => return Err(anyhow::anyhow!("auto format is only for decoding")),
+    };
+    // 默认 standard 带 padding，urlsafe 不带
+    let padding = match (style.pad || style.mime, style.no_pad) {
+        (true, _) => true,
+        (_, true) => false,
+        _ => format == Base64Format::Standard,
+    };
+    let engine = GeneralPurpose::new(
+        alphabet,
+        GeneralPurposeConfig::new().with_encode_padding(padding),
+    );
+
+    let (width, eol): (usize, &[u8]) = if style.mime {
+        (MIME_LINE_WIDTH, b"\r\n")
+    } else {
+        (style.wrap, b"\n")
     };
-    Ok(encoded)
+    let mut wrapped = LineWrap {
+        inner: writer,
+        width,
+        col: 0,
+        eol,
+    };
+    let mut encoder = EncoderWriter::new(&mut wrapped, &engine);
+    copy_chunked(reader, &mut encoder)?;
+    // finish 会把最后不足 3 个字节的数据和 padding 写出去
+    encoder.finish()?.finish()?;
+    Ok(())
 }
 
-pub fn process_decode(input: &str, format: Base64Format) -> anyhow::Result<Vec<u8>> {
-    let mut reader = get_reader(input)?;
-    let mut buf = String::new();
-    reader.read_to_string(&mut buf)?;
-    // avoid accidental newlines
-    let buf = buf.trim();
+pub fn process_decode(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    format: Base64Format,
+) -> anyhow::Result<()> {
+    let mut decoder = base64_reader(reader, format);
+    copy_chunked(&mut decoder, writer)?;
+    writer.flush()?;
+    Ok(())
+}
 
-    let decoded = match format {
-        Base64Format::Standard => STANDARD.decode(buf)?,
-        Base64Format::UrlSafe => URL_SAFE_NO_PAD.decode(buf)?,
+/// Reader that decodes base64 on the fly, so it can be chained with other decoders
+pub(crate) fn base64_reader<'a>(reader: &'a mut dyn Read, format: Base64Format) -> impl Read + 'a {
+    let engine = match format {
+        Base64Format::Standard => &STANDARD_DECODER,
+        Base64Format::UrlSafe => &URL_SAFE_DECODER,
+        Base64Format::Auto => &AUTO_DECODER,
     };
-    // TODO: decoded data might not be string(but for this example, we assume it is)
-    // let decoded = String::from_utf8(decoded)?;
-    Ok(decoded)
+    // avoid accidental newlines, even in the middle of the input
+    let input = Base64Input {
+        inner: reader,
+        url_to_standard: format == Base64Format::Auto,
+    };
+    DecoderReader::new(input, engine)
 }
 
-#[cfg(test)]
-use anyhow::Result;
-#[test]
-fn test_encode() -> Result<()> {
-    let input = "Cargo.toml";
-    let format = Base64Format::UrlSafe;
-    process_encode(input, format)?;
+fn copy_chunked(reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_all(&buf[..n])?;
+    }
+}
 
-    Ok(())
+/// Writer adapter that breaks the output into lines of `width` bytes, 0 disables it
+struct LineWrap<'a> {
+    inner: &'a mut dyn Write,
+    width: usize,
+    col: usize,
+    eol: &'static [u8],
 }
 
-#[test]
-fn test_decode() -> Result<()> {
-    let input = "fixtures/b64.txt";
-    let format = Base64Format::UrlSafe;
-    process_decode(input, format).unwrap();
+impl LineWrap<'_> {
+    /// Terminate the last partial line
+    fn finish(&mut self) -> io::Result<()> {
+        if self.width > 0 && self.col > 0 {
+            self.inner.write_all(self.eol)?;
+            self.col = 0;
+        }
+        self.inner.flush()
+    }
+}
 
-    Ok(())
+impl Write for LineWrap<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.width == 0 {
+            return self.inner.write(buf);
+        }
+        let mut rest = buf;
+        while !rest.is_empty() {
+            let take = rest.len().min(self.width - self.col);
+            self.inner.write_all(&rest[..take])?;
+            self.col += take;
+            rest = &rest[take..];
+            if self.col == self.width {
+                self.inner.write_all(self.eol)?;
+                self.col = 0;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader adapter that drops ASCII whitespace, so wrapped base64 can be decoded
+struct Base64Input<R> {
+    inner: R,
+    url_to_standard: bool,
+}
+
+impl<R: Read> Read for Base64Input<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let n = self.inner.read(buf)?;
+            if n == 0 {
+                return Ok(0);
+            }
+            let mut len = 0;
+            for i in 0..n {
+                let c = match buf[i] {
+                    b'-' if self.url_to_standard => b'+',
+                    b'_' if self.url_to_standard => b'/',
+                    c => c,
+                };
+                if !c.is_ascii_whitespace() {
+                    buf[len] = c;
+                    len += 1;
+                }
+            }
+            // 读到的全是空白就继续读，返回 0 会被当成 EOF
+            if len > 0 {
+                return Ok(len);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_reader, get_writer};
+    use anyhow::Result;
+
+    #[test]
+    fn test_encode() -> Result<()> {
+        let mut reader = get_reader("Cargo.toml")?;
+        let format = Base64Format::UrlSafe;
+        let mut encoded = Vec::new();
+        process_encode(&mut reader, &mut encoded, format, Default::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode() -> Result<()> {
+        let mut reader = get_reader("fixtures/b64.txt")?;
+        let format = Base64Format::UrlSafe;
+        let mut decoded = Vec::new();
+        process_decode(&mut reader, &mut decoded, format).unwrap();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_wrapped_binary() -> Result<()> {
+        // 超过一个块大小，并且在中间插入换行
+        let data: Vec<u8> = (0..CHUNK_SIZE * 2 + 7).map(|i| (i % 251) as u8).collect();
+        let mut encoded = Vec::new();
+        process_encode(
+            &mut &data[..],
+            &mut encoded,
+            Base64Format::Standard,
+            Default::default(),
+        )?;
+        let wrapped: Vec<u8> = encoded
+            .chunks(76)
+            .flat_map(|line| line.iter().copied().chain(*b"\r\n"))
+            .collect();
+
+        let mut decoded = Vec::new();
+        process_decode(&mut &wrapped[..], &mut decoded, Base64Format::Standard)?;
+        assert_eq!(decoded, data);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_to_file() -> Result<()> {
+        // 解出来的是二进制，-o 写文件时要原样保留，不能经过 utf8
+        let data: Vec<u8> = (0..=255u8).rev().chain(0..=255).collect();
+        let mut encoded = Vec::new();
+        process_encode(
+            &mut &data[..],
+            &mut encoded,
+            Base64Format::Standard,
+            Default::default(),
+        )?;
+
+        let path = std::env::temp_dir().join(format!("rcli-b64-{}.bin", std::process::id()));
+        let output = path.display().to_string();
+        let mut writer = get_writer(&output)?;
+        process_decode(&mut &encoded[..], &mut writer, Base64Format::Standard)?;
+        drop(writer);
+        assert_eq!(std::fs::read(&path)?, data);
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_any_padding() -> Result<()> {
+        let mut outputs = Vec::new();
+        for input in ["fixtures/b64.txt", "fixtures/standard_b64.txt"] {
+            for format in [
+                Base64Format::Standard,
+                Base64Format::UrlSafe,
+                Base64Format::Auto,
+            ] {
+                let mut decoded = Vec::new();
+                process_decode(&mut get_reader(input)?, &mut decoded, format)?;
+                outputs.push(decoded);
+            }
+        }
+        assert!(outputs.windows(2).all(|w| w[0] == w[1]));
+        assert!(outputs[0].starts_with(b"[package]"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_styles() -> Result<()> {
+        let data = [0xfbu8, 0xff, 0xbf, 0x01];
+        let encode = |format, style| -> Result<String> {
+            let mut out = Vec::new();
+            process_encode(&mut &data[..], &mut out, format, style)?;
+            Ok(String::from_utf8(out)?)
+        };
+        let pad = Base64EncodeStyle {
+            pad: true,
+            ..Default::default()
+        };
+        assert_eq!(encode(Base64Format::UrlSafe, Default::default())?, "-_-_AQ");
+        assert_eq!(encode(Base64Format::UrlSafe, pad)?, "-_-_AQ==");
+        let no_pad = Base64EncodeStyle {
+            no_pad: true,
+            ..Default::default()
+        };
+        assert_eq!(encode(Base64Format::Standard, no_pad)?, "+/+/AQ");
+        let wrap = Base64EncodeStyle {
+            wrap: 4,
+            ..Default::default()
+        };
+        assert_eq!(encode(Base64Format::Standard, wrap)?, "+/+/\nAQ==\n");
+
+        // auto 能识别两种字母表
+        for input in ["-_-_AQ", "+/+/AQ=="] {
+            let mut decoded = Vec::new();
+            process_decode(&mut input.as_bytes(), &mut decoded, Base64Format::Auto)?;
+            assert_eq!(decoded, data);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,329 @@
+use std::io::{Read, Write};
+
+use anyhow::Result;
+use base32::Alphabet;
+
+use crate::{process_decode, process_encode, Base64EncodeStyle, Base64Format, CodecFormat};
+
+const Z85: &[u8] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+/// Options that only apply to some of the formats
+#[derive(Debug, Clone, Default)]
+pub struct CodecOptions {
+    /// hex: uppercase digits
+    pub upper: bool,
+    /// hex: separator between bytes
+    pub separator: String,
+    /// base58: append/verify a 4-byte double SHA-256 checksum
+    pub check: bool,
+    /// base32 and base64: don't pad the output
+    pub no_pad: bool,
+}
+
+pub fn process_codec_encode(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    format: CodecFormat,
+    opts: &CodecOptions,
+) -> Result<()> {
+    // base64 走流式编码，不需要把输入全部读进内存
+    if let Some(b64) = format.base64() {
+        let style = Base64EncodeStyle {
+            no_pad: opts.no_pad,
+            ..Default::default()
+        };
+        return process_encode(reader, writer, b64, style);
+    }
+
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    let encoded = match format {
+        CodecFormat::Hex => encode_hex(&data, opts.upper, &opts.separator),
+        CodecFormat::Base32 => base32::encode(
+            Alphabet::Rfc4648 {
+                padding: !opts.no_pad,
+            },
+            &data,
+        ),
+        CodecFormat::Base32Crockford => base32::encode(Alphabet::Crockford, &data),
+        CodecFormat::ZBase32 => base32::encode(Alphabet::Z, &data),
+        CodecFormat::Base58 if opts.check => bs58::encode(&data).with_check().into_string(),
+        CodecFormat::Base58 => bs58::encode(&data).into_string(),
+        CodecFormat::Ascii85 => encode_ascii85(&data),
+        CodecFormat::Z85 => encode_z85(&data)?,
+        CodecFormat::Base64 | CodecFormat::Base64Url => unreachable!("handled by process_encode"),
+    };
+    writer.write_all(encoded.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn process_codec_decode(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    format: CodecFormat,
+    opts: &CodecOptions,
+) -> Result<()> {
+    if let Some(b64) = format.base64() {
+        return process_decode(reader, writer, b64);
+    }
+
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    // 换行和空白在这些格式里都没有意义
+    let input: String = input.split_whitespace().collect();
+    let invalid = || anyhow::anyhow!("invalid {} input", format);
+    let decoded = match format {
+        CodecFormat::Hex => decode_hex(&input, &opts.separator)?,
+        CodecFormat::Base32 => {
+            let input = input.trim_end_matches('=');
+            base32::decode(Alphabet::Rfc4648 { padding: false }, &input.to_uppercase())
+                .ok_or_else(invalid)?
+        }
+        CodecFormat::Base32Crockford => {
+            // Crockford 允许用 - 分组，并且 I L O 会被当作 1 1 0
+            let input: String = input
+                .chars()
+                .filter(|c| *c != '-')
+                .map(|c| match c.to_ascii_uppercase() {
+                    'I' | 'L' => '1',
+                    'O' => '0',
+                    c => c,
+                })
+                .collect();
+            base32::decode(Alphabet::Crockford, &input).ok_or_else(invalid)?
+        }
+        CodecFormat::ZBase32 => base32::decode(Alphabet::Z, &input).ok_or_else(invalid)?,
+        CodecFormat::Base58 if opts.check => bs58::decode(&input).with_check(None).into_vec()?,
+        CodecFormat::Base58 => bs58::decode(&input).into_vec()?,
+        CodecFormat::Ascii85 => decode_ascii85(&input)?,
+        CodecFormat::Z85 => decode_z85(&input)?,
+        CodecFormat::Base64 | CodecFormat::Base64Url => unreachable!("handled by process_decode"),
+    };
+    writer.write_all(&decoded)?;
+    writer.flush()?;
+    Ok(())
+}
+
+impl CodecFormat {
+    fn base64(self) -> Option<Base64Format> {
+        match self {
+            CodecFormat::Base64 => Some(Base64Format::Standard),
+            CodecFormat::Base64Url => Some(Base64Format::UrlSafe),
+            _ => None,
+        }
+    }
+}
+
+fn encode_hex(data: &[u8], upper: bool, separator: &str) -> String {
+    data.iter()
+        .map(|b| {
+            if upper {
+                format!("{:02X}", b)
+            } else {
+                format!("{:02x}", b)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn decode_hex(input: &str, separator: &str) -> Result<Vec<u8>> {
+    let input = input.strip_prefix("0x").unwrap_or(input);
+    let input = if separator.is_empty() {
+        input.to_string()
+    } else {
+        input.replace(separator, "")
+    };
+    // from_str_radix 会接受开头的 +，先逐个字符检查
+    if let Some(c) = input.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(anyhow::anyhow!("invalid hex digit {:?}", c));
+    }
+    if input.len() % 2 != 0 {
+        return Err(anyhow::anyhow!("hex input has an odd number of digits"));
+    }
+    (0..input.len())
+        .step_by(2)
+        .map(|i| Ok(u8::from_str_radix(&input[i..i + 2], 16)?))
+        .collect()
+}
+
+/// Adobe/btoa Ascii85 without the `<~ ~>` delimiters, `z` stands for four zero bytes
+fn encode_ascii85(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len() * 5 / 4 + 5);
+    for chunk in data.chunks(4) {
+        let mut block = [0u8; 4];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let value = u32::from_be_bytes(block);
+        if value == 0 && chunk.len() == 4 {
+            out.push('z');
+            continue;
+        }
+        let digits = base85_digits(value);
+        // 最后不足 4 字节的块只输出 n + 1 个字符
+        for d in &digits[..chunk.len() + 1] {
+            out.push((d + b'!') as char);
+        }
+    }
+    out
+}
+
+fn decode_ascii85(input: &str) -> Result<Vec<u8>> {
+    let input = input.trim_start_matches("<~").trim_end_matches("~>");
+    let mut out = Vec::with_capacity(input.len() * 4 / 5 + 4);
+    let mut group = Vec::with_capacity(5);
+    for c in input.bytes() {
+        if c == b'z' && group.is_empty() {
+            out.extend_from_slice(&[0; 4]);
+            continue;
+        }
+        if !(b'!'..=b'u').contains(&c) {
+            return Err(anyhow::anyhow!("invalid ascii85 character {:?}", c as char));
+        }
+        group.push(c - b'!');
+        if group.len() == 5 {
+            out.extend_from_slice(&base85_value(&group)?.to_be_bytes());
+            group.clear();
+        }
+    }
+    if !group.is_empty() {
+        if group.len() == 1 {
+            return Err(anyhow::anyhow!("truncated ascii85 input"));
+        }
+        // 用最大的数字 u 补齐，再截掉补上的字节
+        let n = group.len() - 1;
+        group.resize(5, 84);
+        out.extend_from_slice(&base85_value(&group)?.to_be_bytes()[..n]);
+    }
+    Ok(out)
+}
+
+/// ZeroMQ Z85, the input length must be a multiple of 4
+fn encode_z85(data: &[u8]) -> Result<String> {
+    if !data.len().is_multiple_of(4) {
+        return Err(anyhow::anyhow!("z85 input length must be a multiple of 4"));
+    }
+    let mut out = String::with_capacity(data.len() * 5 / 4);
+    for chunk in data.chunks(4) {
+        let value = u32::from_be_bytes(chunk.try_into()?);
+        for d in base85_digits(value) {
+            out.push(Z85[d as usize] as char);
+        }
+    }
+    Ok(out)
+}
+
+fn decode_z85(input: &str) -> Result<Vec<u8>> {
+    if !input.len().is_multiple_of(5) {
+        return Err(anyhow::anyhow!("z85 input length must be a multiple of 5"));
+    }
+    let mut out = Vec::with_capacity(input.len() * 4 / 5);
+    for chunk in input.as_bytes().chunks(5) {
+        let mut group = [0u8; 5];
+        for (d, c) in group.iter_mut().zip(chunk) {
+            *d = Z85
+                .iter()
+                .position(|z| z == c)
+                .ok_or_else(|| anyhow::anyhow!("invalid z85 character {:?}", *c as char))?
+                as u8;
+        }
+        out.extend_from_slice(&base85_value(&group)?.to_be_bytes());
+    }
+    Ok(out)
+}
+
+fn base85_digits(mut value: u32) -> [u8; 5] {
+    let mut digits = [0u8; 5];
+    for d in digits.iter_mut().rev() {
+        *d = (value % 85) as u8;
+        value /= 85;
+    }
+    digits
+}
+
+fn base85_value(digits: &[u8]) -> Result<u32> {
+    let value = digits
+        .iter()
+        .try_fold(0u32, |acc, d| acc.checked_mul(85)?.checked_add(*d as u32));
+    value.ok_or_else(|| anyhow::anyhow!("base85 group overflows 32 bits"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(format: CodecFormat, opts: &CodecOptions, data: &[u8]) -> Result<String> {
+        let mut encoded = Vec::new();
+        process_codec_encode(&mut &data[..], &mut encoded, format, opts)?;
+        let mut decoded = Vec::new();
+        process_codec_decode(&mut &encoded[..], &mut decoded, format, opts)?;
+        assert_eq!(decoded, data, "{}", format);
+        Ok(String::from_utf8(encoded)?)
+    }
+
+    #[test]
+    fn test_codec_known_vectors() -> Result<()> {
+        let opts = CodecOptions::default();
+        assert_eq!(roundtrip(CodecFormat::Hex, &opts, b"\x01\xab")?, "01ab");
+        let hex = CodecOptions {
+            upper: true,
+            separator: ":".into(),
+            ..Default::default()
+        };
+        assert_eq!(roundtrip(CodecFormat::Hex, &hex, b"\x01\xab")?, "01:AB");
+        assert_eq!(
+            roundtrip(CodecFormat::Base32, &opts, b"foobar")?,
+            "MZXW6YTBOI======"
+        );
+        assert_eq!(
+            roundtrip(CodecFormat::Base58, &opts, b"hello world")?,
+            "StV1DL6CwTryKyV"
+        );
+        assert_eq!(
+            roundtrip(CodecFormat::Ascii85, &opts, b"Man is d")?,
+            "9jqo^BlbD-"
+        );
+        assert_eq!(
+            roundtrip(
+                CodecFormat::Z85,
+                &opts,
+                &[0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B]
+            )?,
+            "HelloWorld"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_codec_roundtrip() -> Result<()> {
+        let data: Vec<u8> = (0..=255u8).chain([0, 0, 0, 0, 1]).collect();
+        let check = CodecOptions {
+            check: true,
+            ..Default::default()
+        };
+        for format in [
+            CodecFormat::Base64,
+            CodecFormat::Base64Url,
+            CodecFormat::Hex,
+            CodecFormat::Base32,
+            CodecFormat::Base32Crockford,
+            CodecFormat::ZBase32,
+            CodecFormat::Base58,
+            CodecFormat::Ascii85,
+        ] {
+            roundtrip(format, &CodecOptions::default(), &data)?;
+        }
+        roundtrip(CodecFormat::Base58, &check, &data)?;
+        roundtrip(CodecFormat::Z85, &check, &data[..256])?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_codec_hex_rejects_sign() {
+        assert!(decode_hex("+f", "").is_err());
+        assert!(decode_hex("00+f", "").is_err());
+        assert!(decode_hex("é0", "").is_err());
+        assert_eq!(decode_hex("0x00ff", "").unwrap(), [0, 0xff]);
+    }
+}
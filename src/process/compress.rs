@@ -0,0 +1,173 @@
+use std::{
+    io::{self, Read, Write},
+    ops::RangeInclusive,
+};
+
+use anyhow::Result;
+use flate2::{
+    read::{DeflateDecoder, DeflateEncoder, GzEncoder, MultiGzDecoder, ZlibDecoder, ZlibEncoder},
+    Compression,
+};
+
+use super::b64::base64_reader;
+use crate::{process_encode, Base64Format, CompressFormat};
+
+// brotli 内部缓冲区大小和默认窗口
+const BROTLI_BUFFER_SIZE: usize = 64 * 1024;
+const BROTLI_LGWIN: u32 = 22;
+
+/// Compress the input, base64 encoding the compressed stream when `base64` is set
+pub fn process_compress(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    format: CompressFormat,
+    level: Option<u32>,
+    base64: bool,
+) -> Result<()> {
+    let mut compressed = compress_reader(reader, format, level)?;
+    if base64 {
+        return process_encode(
+            &mut compressed,
+            writer,
+            Base64Format::Standard,
+            Default::default(),
+        );
+    }
+    io::copy(&mut compressed, writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Decompress the input, base64 decoding it first when `base64` is set
+pub fn process_decompress(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    format: CompressFormat,
+    base64: bool,
+) -> Result<()> {
+    let mut decoded;
+    let reader: &mut dyn Read = if base64 {
+        decoded = base64_reader(reader, Base64Format::Auto);
+        &mut decoded
+    } else {
+        reader
+    };
+    let mut decompressed = decompress_reader(reader, format)?;
+    io::copy(&mut decompressed, writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn compress_reader<'a>(
+    reader: &'a mut dyn Read,
+    format: CompressFormat,
+    level: Option<u32>,
+) -> Result<Box<dyn Read + 'a>> {
+    let (range, default) = level_range(format);
+    let level = level.unwrap_or(default);
+    if !range.contains(&level) {
+        return Err(anyhow::anyhow!(
+            "{} level must be between {} and {}",
+            format,
+            range.start(),
+            range.end()
+        ));
+    }
+    let compressed: Box<dyn Read> = match format {
+        CompressFormat::Gzip => Box::new(GzEncoder::new(reader, Compression::new(level))),
+        CompressFormat::Zlib => Box::new(ZlibEncoder::new(reader, Compression::new(level))),
+        CompressFormat::Deflate => Box::new(DeflateEncoder::new(reader, Compression::new(level))),
+        CompressFormat::Zstd => Box::new(zstd::stream::read::Encoder::new(reader, level as i32)?),
+        CompressFormat::Brotli => Box::new(brotli::CompressorReader::new(
+            reader,
+            BROTLI_BUFFER_SIZE,
+            level,
+            BROTLI_LGWIN,
+        )),
+        CompressFormat::Xz => Box::new(xz2::read::XzEncoder::new(reader, level)),
+    };
+    Ok(compressed)
+}
+
+fn decompress_reader<'a>(
+    reader: &'a mut dyn Read,
+    format: CompressFormat,
+) -> Result<Box<dyn Read + 'a>> {
+    let decompressed: Box<dyn Read> = match format {
+        // 多个 gzip member 拼在一起也要能完整解出来
+        CompressFormat::Gzip => Box::new(MultiGzDecoder::new(reader)),
+        CompressFormat::Zlib => Box::new(ZlibDecoder::new(reader)),
+        CompressFormat::Deflate => Box::new(DeflateDecoder::new(reader)),
+        CompressFormat::Zstd => Box::new(zstd::Decoder::new(reader)?),
+        CompressFormat::Brotli => Box::new(brotli::Decompressor::new(reader, BROTLI_BUFFER_SIZE)),
+        CompressFormat::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(reader)),
+    };
+    Ok(decompressed)
+}
+
+/// Valid levels and the default level of each format
+fn level_range(format: CompressFormat) -> (RangeInclusive<u32>, u32) {
+    match format {
+        CompressFormat::Gzip | CompressFormat::Zlib | CompressFormat::Deflate => (0..=9, 6),
+        CompressFormat::Zstd => (1..=22, 3),
+        CompressFormat::Brotli => (0..=11, 9),
+        CompressFormat::Xz => (0..=9, 6),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress_roundtrip() -> Result<()> {
+        let data: Vec<u8> = b"rcli compress ".repeat(1000);
+        for format in [
+            CompressFormat::Gzip,
+            CompressFormat::Zlib,
+            CompressFormat::Deflate,
+            CompressFormat::Zstd,
+            CompressFormat::Brotli,
+            CompressFormat::Xz,
+        ] {
+            for base64 in [false, true] {
+                let mut compressed = Vec::new();
+                process_compress(&mut &data[..], &mut compressed, format, None, base64)?;
+                assert!(compressed.len() < data.len() / 10, "{}", format);
+
+                let mut decompressed = Vec::new();
+                process_decompress(&mut &compressed[..], &mut decompressed, format, base64)?;
+                assert_eq!(decompressed, data, "{}", format);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_levels() -> Result<()> {
+        let mut out = Vec::new();
+        let gzip = |level| {
+            process_compress(
+                &mut &b"x"[..],
+                &mut Vec::new(),
+                CompressFormat::Gzip,
+                level,
+                false,
+            )
+        };
+        assert!(gzip(Some(0)).is_ok());
+        assert!(gzip(Some(10)).is_err());
+        process_compress(
+            &mut &b"x"[..],
+            &mut out,
+            CompressFormat::Zstd,
+            Some(19),
+            false,
+        )?;
+        assert!(
+            process_decompress(&mut &b"not zstd"[..], &mut out, CompressFormat::Zstd, false)
+                .is_err()
+        );
+        Ok(())
+    }
+}
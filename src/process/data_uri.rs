@@ -0,0 +1,168 @@
+use std::{
+    io::{Read, Write},
+    path::Path,
+};
+
+use anyhow::Result;
+use percent_encoding::percent_decode_str;
+
+use crate::{process_decode, process_encode, Base64Format};
+
+// 只需要读开头几个字节就能识别常见的文件类型
+const SNIFF_LEN: u64 = 16;
+
+const MAGIC: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"\x00\x00\x01\x00", "image/x-icon"),
+    (b"wOFF", "font/woff"),
+    (b"wOF2", "font/woff2"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"PK\x03\x04", "application/zip"),
+];
+
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+    ("ico", "image/x-icon"),
+    ("pdf", "application/pdf"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("css", "text/css"),
+    ("html", "text/html"),
+    ("js", "text/javascript"),
+    ("json", "application/json"),
+    ("txt", "text/plain"),
+];
+
+/// Encode the input as `data:<mime>;base64,...`, detecting the MIME type when not given
+pub fn process_datauri_encode(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    mime: Option<&str>,
+    filename: Option<&str>,
+) -> Result<()> {
+    let mut head = Vec::new();
+    (&mut *reader).take(SNIFF_LEN).read_to_end(&mut head)?;
+
+    let mime = match mime {
+        Some(mime) => validate_mime(mime)?.to_string(),
+        None => detect_mime(&head, filename).to_string(),
+    };
+    write!(writer, "data:{};base64,", mime)?;
+    // 把已经读出来的开头部分和剩下的内容接起来继续流式编码
+    let mut rest = head.as_slice().chain(reader);
+    process_encode(
+        &mut rest,
+        writer,
+        Base64Format::Standard,
+        Default::default(),
+    )
+}
+
+/// Parse and validate a data URI, write its payload and return the MIME type
+pub fn process_datauri_decode(reader: &mut dyn Read, writer: &mut dyn Write) -> Result<String> {
+    let mut uri = String::new();
+    reader.read_to_string(&mut uri)?;
+    let uri = uri.trim();
+    let rest = uri
+        .strip_prefix("data:")
+        .ok_or_else(|| anyhow::anyhow!("data URI must start with data:"))?;
+    let (meta, payload) = rest
+        .split_once(',')
+        .ok_or_else(|| anyhow::anyhow!("data URI is missing the ',' separator"))?;
+
+    let (meta, base64) = match meta.strip_suffix(";base64") {
+        Some(meta) => (meta, true),
+        None => (meta, false),
+    };
+    // RFC 2397: 省略类型时默认是 text/plain;charset=US-ASCII
+    let mime = if meta.is_empty() || meta.starts_with(';') {
+        format!("text/plain{}", meta)
+    } else {
+        let (mime, _) = meta.split_once(';').unwrap_or((meta, ""));
+        validate_mime(mime)?;
+        meta.to_string()
+    };
+
+    if base64 {
+        let payload = percent_decode_str(payload).collect::<Vec<u8>>();
+        process_decode(&mut payload.as_slice(), writer, Base64Format::Auto)?;
+    } else {
+        writer.write_all(&percent_decode_str(payload).collect::<Vec<u8>>())?;
+        writer.flush()?;
+    }
+    Ok(mime)
+}
+
+fn detect_mime(head: &[u8], filename: Option<&str>) -> &'static str {
+    if let Some((_, mime)) = MAGIC.iter().find(|(magic, _)| head.starts_with(magic)) {
+        return mime;
+    }
+    if head.starts_with(b"RIFF") && head.get(8..12) == Some(b"WEBP") {
+        return "image/webp";
+    }
+    let ext = filename
+        .and_then(|f| Path::new(f).extension())
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    ext.and_then(|ext| EXTENSIONS.iter().find(|(e, _)| *e == ext))
+        .map(|(_, mime)| *mime)
+        .unwrap_or("application/octet-stream")
+}
+
+fn validate_mime(mime: &str) -> Result<&str> {
+    let token = |s: &str| {
+        !s.is_empty()
+            && s.bytes()
+                .all(|c| c.is_ascii_alphanumeric() || b"!#$&-^_.+".contains(&c))
+    };
+    match mime.split_once('/') {
+        Some((t, s)) if token(t) && token(s) => Ok(mime),
+        _ => Err(anyhow::anyhow!("invalid MIME type: {}", mime)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_datauri_roundtrip() -> Result<()> {
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR rest of the image";
+        let mut uri = Vec::new();
+        process_datauri_encode(&mut &png[..], &mut uri, None, None)?;
+        let uri = String::from_utf8(uri)?;
+        assert!(uri.starts_with("data:image/png;base64,iVBORw0KGgo"));
+
+        let mut payload = Vec::new();
+        let mime = process_datauri_decode(&mut uri.as_bytes(), &mut payload)?;
+        assert_eq!(mime, "image/png");
+        assert_eq!(payload, png);
+        Ok(())
+    }
+
+    #[test]
+    fn test_datauri_decode_plain() -> Result<()> {
+        let mut payload = Vec::new();
+        let mime = process_datauri_decode(&mut "data:,A%20brief%20note".as_bytes(), &mut payload)?;
+        assert_eq!(mime, "text/plain");
+        assert_eq!(payload, b"A brief note");
+
+        let mut uri = Vec::new();
+        process_datauri_encode(&mut "a { }".as_bytes(), &mut uri, None, Some("site.CSS"))?;
+        assert_eq!(uri, b"data:text/css;base64,YSB7IH0=");
+
+        assert!(process_datauri_decode(&mut "data:bad mime,xx".as_bytes(), &mut payload).is_err());
+        assert!(process_datauri_decode(&mut "nope".as_bytes(), &mut payload).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,379 @@
+use std::{
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use anyhow::Result;
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::{rngs::OsRng, RngCore};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, CHACHA20_POLY1305};
+
+use super::key::symmetric_key;
+use crate::{Key, TextEncryptFormat, TextSignFormat};
+
+// 文件格式:
+//   magic(7) | version(1) | algorithm(1) | kdf(1) | [m_cost(4) t_cost(4) p_cost(4) salt(16)]
+//   | nonce prefix(7) | chunk size(4)
+// 之后每个块是 密文 + 16 字节 tag，nonce = prefix | 块序号(4) | 是否最后一块(1)
+// 整个 header 作为每个块的 AAD，改动 header 任何一个字节都会解密失败
+const MAGIC: &[u8; 7] = b"RCLIENC";
+const VERSION: u8 = 1;
+const KDF_NONE: u8 = 0;
+const KDF_ARGON2ID: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_PREFIX_LEN: usize = 7;
+const TAG_LEN: usize = 16;
+const CHUNK_SIZE: u32 = 64 * 1024;
+// 解密时拒绝过大的参数，避免恶意 header 耗尽内存
+const MAX_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+const MAX_M_COST: u32 = 1024 * 1024;
+const MAX_T_COST: u32 = 64;
+const MAX_P_COST: u32 = 16;
+
+const ARGON2_M_COST: u32 = 19 * 1024;
+const ARGON2_T_COST: u32 = 2;
+const ARGON2_P_COST: u32 = 1;
+
+/// Where the 256-bit encryption key comes from
+pub enum CryptKey {
+    /// raw key, e.g. a key file from `text generate`
+    Raw([u8; 32]),
+    /// passphrase stretched with Argon2id and a random salt
+    Passphrase(String),
+}
+
+impl CryptKey {
+    /// Load a 32-byte symmetric key file, PEM/JWK and encrypted key files are decoded first
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match Key::load(path, TextSignFormat::Blake3, true)? {
+            Key::Symmetric(key) => Ok(CryptKey::Raw(symmetric_key(&key)?)),
+            key => Err(key.mismatch("symmetric")),
+        }
+    }
+}
+
+/// Encrypt the input in authenticated chunks behind a versioned header
+pub fn process_text_encrypt(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    key: &CryptKey,
+    format: TextEncryptFormat,
+) -> Result<()> {
+    let mut header = MAGIC.to_vec();
+    header.push(VERSION);
+    header.push(algorithm_id(format));
+    let key = match key {
+        CryptKey::Raw(key) => {
+            header.push(KDF_NONE);
+            *key
+        }
+        CryptKey::Passphrase(passphrase) => {
+            let mut salt = [0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            header.push(KDF_ARGON2ID);
+            for n in [ARGON2_M_COST, ARGON2_T_COST, ARGON2_P_COST] {
+                header.extend_from_slice(&n.to_be_bytes());
+            }
+            header.extend_from_slice(&salt);
+            derive_key(
+                passphrase,
+                &salt,
+                ARGON2_M_COST,
+                ARGON2_T_COST,
+                ARGON2_P_COST,
+            )?
+        }
+    };
+    let mut prefix = [0u8; NONCE_PREFIX_LEN];
+    OsRng.fill_bytes(&mut prefix);
+    header.extend_from_slice(&prefix);
+    header.extend_from_slice(&CHUNK_SIZE.to_be_bytes());
+    writer.write_all(&header)?;
+
+    let key = aead_key(format, &key)?;
+    let mut chunks = ChunkReader::new(reader, CHUNK_SIZE as usize);
+    let mut counter = 0u32;
+    loop {
+        let (mut chunk, last) = chunks.next()?;
+        let nonce = chunk_nonce(&prefix, counter, last);
+        let tag = key
+            .seal_in_place_separate_tag(nonce, Aad::from(&header), &mut chunk)
+            .map_err(|_| anyhow::anyhow!("failed to encrypt chunk {}", counter))?;
+        writer.write_all(&chunk)?;
+        writer.write_all(tag.as_ref())?;
+        if last {
+            break;
+        }
+        counter = next_counter(counter)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Whether the data starts with the header written by [`process_text_encrypt`]
+pub(crate) fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Decrypt a file written by [`process_text_encrypt`], failing on any tampering or truncation
+pub fn process_text_decrypt(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    key: &CryptKey,
+) -> Result<()> {
+    let mut header = vec![0u8; MAGIC.len() + 3];
+    reader
+        .read_exact(&mut header)
+        .map_err(|_| anyhow::anyhow!("input is too short to be encrypted by rcli"))?;
+    if &header[..MAGIC.len()] != MAGIC {
+        return Err(anyhow::anyhow!("input is not encrypted by rcli"));
+    }
+    let (version, algorithm, kdf) = (header[7], header[8], header[9]);
+    if version != VERSION {
+        return Err(anyhow::anyhow!("unsupported format version {}", version));
+    }
+    let format = match algorithm {
+        1 => TextEncryptFormat::ChaCha20Poly1305,
+        2 => TextEncryptFormat::Aes256Gcm,
+        n => return Err(anyhow::anyhow!("unknown algorithm {}", n)),
+    };
+
+    let key = match (kdf, key) {
+        (KDF_NONE, CryptKey::Raw(key)) => *key,
+        (KDF_ARGON2ID, CryptKey::Passphrase(passphrase)) => {
+            let mut params = [0u8; 12 + SALT_LEN];
+            reader.read_exact(&mut params)?;
+            header.extend_from_slice(&params);
+            let n = |i: usize| u32::from_be_bytes(params[i..i + 4].try_into().unwrap());
+            let (m_cost, t_cost, p_cost) = (n(0), n(4), n(8));
+            if m_cost > MAX_M_COST {
+                return Err(anyhow::anyhow!(
+                    "argon2 memory cost {} is too large",
+                    m_cost
+                ));
+            }
+            if t_cost > MAX_T_COST {
+                return Err(anyhow::anyhow!("argon2 time cost {} is too large", t_cost));
+            }
+            if p_cost > MAX_P_COST {
+                return Err(anyhow::anyhow!(
+                    "argon2 parallelism {} is too large",
+                    p_cost
+                ));
+            }
+            derive_key(passphrase, &params[12..], m_cost, t_cost, p_cost)?
+        }
+        (KDF_NONE, _) => return Err(anyhow::anyhow!("input was encrypted with a key file")),
+        (KDF_ARGON2ID, _) => return Err(anyhow::anyhow!("input was encrypted with a passphrase")),
+        (n, _) => return Err(anyhow::anyhow!("unknown key derivation {}", n)),
+    };
+
+    let mut rest = [0u8; NONCE_PREFIX_LEN + 4];
+    reader.read_exact(&mut rest)?;
+    header.extend_from_slice(&rest);
+    let prefix: [u8; NONCE_PREFIX_LEN] = rest[..NONCE_PREFIX_LEN].try_into()?;
+    let chunk_size = u32::from_be_bytes(rest[NONCE_PREFIX_LEN..].try_into()?);
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+        return Err(anyhow::anyhow!("invalid chunk size {}", chunk_size));
+    }
+
+    let key = aead_key(format, &key)?;
+    let mut chunks = ChunkReader::new(reader, chunk_size as usize + TAG_LEN);
+    let mut counter = 0u32;
+    loop {
+        let (mut chunk, last) = chunks.next()?;
+        let nonce = chunk_nonce(&prefix, counter, last);
+        // 被截断的文件最后一块的标记不对，同样会认证失败
+        let plain = key
+            .open_in_place(nonce, Aad::from(&header), &mut chunk)
+            .map_err(|_| anyhow::anyhow!("decryption failed: wrong key or corrupted input"))?;
+        writer.write_all(plain)?;
+        if last {
+            break;
+        }
+        counter = next_counter(counter)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn algorithm_id(format: TextEncryptFormat) -> u8 {
+    match format {
+        TextEncryptFormat::ChaCha20Poly1305 => 1,
+        TextEncryptFormat::Aes256Gcm => 2,
+    }
+}
+
+fn aead_key(format: TextEncryptFormat, key: &[u8; 32]) -> Result<LessSafeKey> {
+    let algorithm = match format {
+        TextEncryptFormat::ChaCha20Poly1305 => &CHACHA20_POLY1305,
+        TextEncryptFormat::Aes256Gcm => &AES_256_GCM,
+    };
+    let key = UnboundKey::new(algorithm, key).map_err(|_| anyhow::anyhow!("invalid key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> Result<[u8; 32]> {
+    let params = Params::new(m_cost, t_cost, p_cost, Some(32))
+        .map_err(|e| anyhow::anyhow!("invalid argon2 params: {}", e))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+fn chunk_nonce(prefix: &[u8; NONCE_PREFIX_LEN], counter: u32, last: bool) -> Nonce {
+    let mut nonce = [0u8; 12];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    Nonce::assume_unique_for_key(nonce)
+}
+
+fn next_counter(counter: u32) -> Result<u32> {
+    counter
+        .checked_add(1)
+        .ok_or_else(|| anyhow::anyhow!("input has too many chunks"))
+}
+
+/// Reads fixed-size chunks and tells whether each one is the last, by reading one byte ahead
+struct ChunkReader<'a> {
+    reader: &'a mut dyn Read,
+    size: usize,
+    peeked: Option<u8>,
+}
+
+impl<'a> ChunkReader<'a> {
+    fn new(reader: &'a mut dyn Read, size: usize) -> Self {
+        Self {
+            reader,
+            size,
+            peeked: None,
+        }
+    }
+
+    fn next(&mut self) -> io::Result<(Vec<u8>, bool)> {
+        let mut chunk = Vec::with_capacity(self.size);
+        chunk.extend(self.peeked.take());
+        (&mut *self.reader)
+            .take((self.size - chunk.len()) as u64)
+            .read_to_end(&mut chunk)?;
+        if chunk.len() < self.size {
+            return Ok((chunk, true));
+        }
+        let mut next = [0u8; 1];
+        let n = loop {
+            match self.reader.read(&mut next) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                n => break n?,
+            }
+        };
+        self.peeked = (n == 1).then_some(next[0]);
+        Ok((chunk, n == 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encrypt(data: &[u8], key: &CryptKey, format: TextEncryptFormat) -> Result<Vec<u8>> {
+        let mut encrypted = Vec::new();
+        process_text_encrypt(&mut &data[..], &mut encrypted, key, format)?;
+        Ok(encrypted)
+    }
+
+    fn decrypt(data: &[u8], key: &CryptKey) -> Result<Vec<u8>> {
+        let mut decrypted = Vec::new();
+        process_text_decrypt(&mut &data[..], &mut decrypted, key)?;
+        Ok(decrypted)
+    }
+
+    #[test]
+    fn test_encrypt_roundtrip() -> Result<()> {
+        let key = CryptKey::load("fixtures/blake3.txt")?;
+        // 空输入、正好一个块、跨块
+        for len in [0, CHUNK_SIZE as usize, CHUNK_SIZE as usize * 2 + 3] {
+            let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+            for format in [
+                TextEncryptFormat::ChaCha20Poly1305,
+                TextEncryptFormat::Aes256Gcm,
+            ] {
+                let encrypted = encrypt(&data, &key, format)?;
+                assert_eq!(decrypt(&encrypted, &key)?, data);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_crypt_key_load() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("rcli-crypt-key-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        // 多了换行以外的内容，或者根本不是对称密钥，都不能截取前 32 字节凑合用
+        for (name, data) in [
+            ("short", &b"0123456789abcdef"[..]),
+            ("junk", &b"0123456789abcdef0123456789abcdef\njunk"[..]),
+        ] {
+            std::fs::write(dir.join(name), data)?;
+            assert!(CryptKey::load(dir.join(name)).is_err());
+        }
+        assert!(CryptKey::load("fixtures/ecdsa-p256.sk").is_err());
+        std::fs::write(dir.join("ok"), b"0123456789abcdef0123456789abcdef\r\n")?;
+        assert!(
+            matches!(CryptKey::load(dir.join("ok"))?, CryptKey::Raw(k) if &k == b"0123456789abcdef0123456789abcdef")
+        );
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_encrypt_rejects_tampering() -> Result<()> {
+        let key = CryptKey::load("fixtures/blake3.txt")?;
+        let data = vec![7u8; CHUNK_SIZE as usize + 10];
+        let encrypted = encrypt(&data, &key, TextEncryptFormat::ChaCha20Poly1305)?;
+
+        let mut flipped = encrypted.clone();
+        flipped[40] ^= 1;
+        assert!(decrypt(&flipped, &key).is_err());
+        // 去掉最后一块，剩下的第一块没有最后一块的标记
+        let header_len = MAGIC.len() + 3 + NONCE_PREFIX_LEN + 4;
+        let truncated = &encrypted[..header_len + CHUNK_SIZE as usize + TAG_LEN];
+        assert!(decrypt(truncated, &key).is_err());
+        assert!(decrypt(&encrypted, &CryptKey::Raw([0; 32])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_encrypt_passphrase() -> Result<()> {
+        let key = CryptKey::Passphrase("correct horse".into());
+        let encrypted = encrypt(b"secret", &key, TextEncryptFormat::Aes256Gcm)?;
+        assert_eq!(decrypt(&encrypted, &key)?, b"secret");
+        assert!(decrypt(&encrypted, &CryptKey::Passphrase("wrong".into())).is_err());
+        assert!(decrypt(&encrypted, &CryptKey::load("fixtures/blake3.txt")?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decrypt_rejects_large_costs() -> Result<()> {
+        let key = CryptKey::Passphrase("correct horse".into());
+        let encrypted = encrypt(b"secret", &key, TextEncryptFormat::Aes256Gcm)?;
+        // m_cost、t_cost、p_cost 紧跟在 magic、version、algorithm、kdf 后面
+        let params = MAGIC.len() + 3;
+        for (offset, cost) in [(0, MAX_M_COST), (4, MAX_T_COST), (8, MAX_P_COST)] {
+            let mut tampered = encrypted.clone();
+            let at = params + offset;
+            tampered[at..at + 4].copy_from_slice(&(cost + 1).to_be_bytes());
+            let err = decrypt(&tampered, &key).unwrap_err();
+            assert!(err.to_string().contains("too large"));
+        }
+        Ok(())
+    }
+}
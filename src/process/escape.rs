@@ -0,0 +1,382 @@
+use std::io::{Read, Write};
+
+use anyhow::Result;
+use percent_encoding::{percent_decode, percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use crate::{UnicodeStyle, UrlComponent};
+
+// RFC 3986 unreserved: ALPHA DIGIT - . _ ~
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+// 路径里的 / 和 sub-delims 保持原样
+const PATH: &AsciiSet = &COMPONENT
+    .remove(b'/')
+    .remove(b':')
+    .remove(b'@')
+    .remove(b'!')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')')
+    .remove(b'*')
+    .remove(b'+')
+    .remove(b',')
+    .remove(b';')
+    .remove(b'=');
+
+// 查询串保留 & = 这些分隔符，但 + 会被当成空格，所以要编码
+const QUERY: &AsciiSet = &PATH.add(b'+').remove(b'?');
+
+// application/x-www-form-urlencoded, 空格单独处理成 +
+const FORM: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'*')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_');
+
+const HTML_ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("hellip", '…'),
+    ("mdash", '—'),
+    ("ndash", '–'),
+    ("laquo", '«'),
+    ("raquo", '»'),
+    ("euro", '€'),
+];
+
+pub fn process_url_encode(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    component: UrlComponent,
+) -> Result<()> {
+    let input = read_input(reader)?;
+    let set = match component {
+        UrlComponent::Component => COMPONENT,
+        UrlComponent::Path => PATH,
+        UrlComponent::Query => QUERY,
+        UrlComponent::Form => FORM,
+    };
+    let encoded = percent_encode(&input, set).to_string();
+    let encoded = match component {
+        UrlComponent::Form => encoded.replace("%20", "+"),
+        _ => encoded,
+    };
+    writer.write_all(encoded.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn process_url_decode(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    component: UrlComponent,
+) -> Result<()> {
+    let mut input = read_input(reader)?;
+    // percent_decode 会原样保留不合法的 %，这里先检查一遍
+    for (i, c) in input.iter().enumerate() {
+        let valid = || {
+            input
+                .get(i + 1..i + 3)
+                .is_some_and(|h| h.iter().all(u8::is_ascii_hexdigit))
+        };
+        if *c == b'%' && !valid() {
+            return Err(anyhow::anyhow!("invalid percent escape at offset {}", i));
+        }
+    }
+    if component == UrlComponent::Form {
+        for c in input.iter_mut().filter(|c| **c == b'+') {
+            *c = b' ';
+        }
+    }
+    let decoded: Vec<u8> = percent_decode(&input).collect();
+    writer.write_all(&decoded)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Escape `& < > " '`, and with `ascii` every non-ASCII character as `&#x..;`
+pub fn process_html_escape(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    ascii: bool,
+) -> Result<()> {
+    let input = read_text(reader)?;
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c if ascii && !c.is_ascii() => out.push_str(&format!("&#x{:x};", c as u32)),
+            c => out.push(c),
+        }
+    }
+    writer.write_all(out.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Replace named and numeric character references, unknown entities are kept as is
+pub fn process_html_unescape(reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+    let input = read_text(reader)?;
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input.as_str();
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..]
+            .find(';')
+            .map(|end| &rest[1..end + 1])
+            .and_then(|name| Some((name, html_entity(name)?)));
+        match entity {
+            Some((name, c)) => {
+                out.push(c);
+                rest = &rest[name.len() + 2..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    writer.write_all(out.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Escape quotes, backslashes, control and non-ASCII characters as a JSON or Rust string body
+pub fn process_unicode_escape(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    style: UnicodeStyle,
+) -> Result<()> {
+    let input = read_text(reader)?;
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_ascii() && !c.is_ascii_control() => out.push(c),
+            c => match style {
+                UnicodeStyle::Rust => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+                // JSON 只有 \uXXXX，BMP 以外的字符要拆成代理对
+                UnicodeStyle::Json => {
+                    let mut units = [0u16; 2];
+                    for unit in c.encode_utf16(&mut units) {
+                        out.push_str(&format!("\\u{:04x}", unit));
+                    }
+                }
+            },
+        }
+    }
+    writer.write_all(out.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Undo JSON (`\uXXXX`, surrogate pairs) and Rust (`\u{..}`, `\xNN`) style escapes
+pub fn process_unicode_unescape(reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+    let input = read_text(reader)?;
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let escaped = chars
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("dangling backslash at end of input"))?;
+        let c = match escaped {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            '0' => '\0',
+            '"' | '\'' | '\\' | '/' => escaped,
+            'x' => {
+                let value = hex_value(&take(&mut chars, 2))?;
+                if value > 0x7f {
+                    return Err(anyhow::anyhow!("\\x escape must be at most 7f"));
+                }
+                value as u8 as char
+            }
+            'u' if chars.as_str().starts_with('{') => {
+                let body = chars.as_str();
+                let end = body
+                    .find('}')
+                    .ok_or_else(|| anyhow::anyhow!("unterminated \\u{{...}} escape"))?;
+                let value = hex_value(&body[1..end])?;
+                chars = body[end + 1..].chars();
+                to_char(value)?
+            }
+            'u' => {
+                let unit = hex_value(&take(&mut chars, 4))?;
+                if (0xd800..0xdc00).contains(&unit) {
+                    // 高位代理后面必须紧跟一个低位代理
+                    let low = chars
+                        .as_str()
+                        .strip_prefix("\\u")
+                        .and_then(|s| s.get(..4))
+                        .map(hex_value)
+                        .transpose()?
+                        .filter(|low| (0xdc00..0xe000).contains(low))
+                        .ok_or_else(|| anyhow::anyhow!("unpaired surrogate \\u{:04x}", unit))?;
+                    chars = chars.as_str()[6..].chars();
+                    to_char(0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00))?
+                } else {
+                    to_char(unit)?
+                }
+            }
+            c => return Err(anyhow::anyhow!("unknown escape \\{}", c)),
+        };
+        out.push(c);
+    }
+    writer.write_all(out.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read the whole input without the trailing line ending added by `echo` or editors
+fn read_input(reader: &mut dyn Read) -> Result<Vec<u8>> {
+    let mut input = Vec::new();
+    reader.read_to_end(&mut input)?;
+    if input.ends_with(b"\n") {
+        input.pop();
+        if input.ends_with(b"\r") {
+            input.pop();
+        }
+    }
+    Ok(input)
+}
+
+fn read_text(reader: &mut dyn Read) -> Result<String> {
+    Ok(String::from_utf8(read_input(reader)?)?)
+}
+
+fn html_entity(name: &str) -> Option<char> {
+    let code = if let Some(hex) = name.strip_prefix("#x").or(name.strip_prefix("#X")) {
+        u32::from_str_radix(hex, 16).ok()?
+    } else if let Some(dec) = name.strip_prefix('#') {
+        dec.parse().ok()?
+    } else {
+        return HTML_ENTITIES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, c)| *c);
+    };
+    char::from_u32(code)
+}
+
+fn take(chars: &mut std::str::Chars, n: usize) -> String {
+    chars.take(n).collect()
+}
+
+fn hex_value(hex: &str) -> Result<u32> {
+    if hex.is_empty() || hex.len() > 6 || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow::anyhow!("invalid hex escape {:?}", hex));
+    }
+    Ok(u32::from_str_radix(hex, 16)?)
+}
+
+fn to_char(value: u32) -> Result<char> {
+    char::from_u32(value).ok_or_else(|| anyhow::anyhow!("invalid unicode scalar {:x}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(f: impl Fn(&mut dyn Read, &mut dyn Write) -> Result<()>, input: &str) -> Result<String> {
+        let mut out = Vec::new();
+        f(&mut input.as_bytes(), &mut out)?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[test]
+    fn test_url_components() -> Result<()> {
+        let input = "/a b/c?x=1&y=a+b#frag";
+        let encode = |component| run(|r, w| process_url_encode(r, w, component), input);
+        assert_eq!(
+            encode(UrlComponent::Component)?,
+            "%2Fa%20b%2Fc%3Fx%3D1%26y%3Da%2Bb%23frag"
+        );
+        assert_eq!(encode(UrlComponent::Path)?, "/a%20b/c%3Fx=1&y=a+b%23frag");
+        assert_eq!(encode(UrlComponent::Query)?, "/a%20b/c?x=1&y=a%2Bb%23frag");
+        assert_eq!(
+            run(
+                |r, w| process_url_encode(r, w, UrlComponent::Form),
+                "a b&c=d*\n"
+            )?,
+            "a+b%26c%3Dd*"
+        );
+
+        let decode = |component, input| run(|r, w| process_url_decode(r, w, component), input);
+        assert_eq!(decode(UrlComponent::Form, "a+b%26c")?, "a b&c");
+        assert_eq!(decode(UrlComponent::Query, "a+b%2B")?, "a+b+");
+        assert!(decode(UrlComponent::Component, "100%").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_html_escape() -> Result<()> {
+        let escaped = run(
+            |r, w| process_html_escape(r, w, true),
+            "<a href=\"x\">Tom & Jerry's café</a>",
+        )?;
+        assert_eq!(
+            escaped,
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s caf&#xe9;&lt;/a&gt;"
+        );
+        assert_eq!(
+            run(process_html_unescape, &escaped)?,
+            "<a href=\"x\">Tom & Jerry's café</a>"
+        );
+        assert_eq!(
+            run(process_html_unescape, "&copy; &#8364;5 &bogus; & &amp")?,
+            "© €5 &bogus; & &amp"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unicode_escape() -> Result<()> {
+        let input = "say \"héllo\"\t🦀";
+        let json = run(
+            |r, w| process_unicode_escape(r, w, UnicodeStyle::Json),
+            input,
+        )?;
+        assert_eq!(json, "say \\\"h\\u00e9llo\\\"\\t\\ud83e\\udd80");
+        let rust = run(
+            |r, w| process_unicode_escape(r, w, UnicodeStyle::Rust),
+            input,
+        )?;
+        assert_eq!(rust, "say \\\"h\\u{e9}llo\\\"\\t\\u{1f980}");
+        assert_eq!(run(process_unicode_unescape, &json)?, input);
+        assert_eq!(run(process_unicode_unescape, &rust)?, input);
+        assert_eq!(run(process_unicode_unescape, "\\x41\\/")?, "A/");
+        assert!(run(process_unicode_unescape, "\\ud83e").is_err());
+        assert!(run(process_unicode_unescape, "\\q").is_err());
+        Ok(())
+    }
+}
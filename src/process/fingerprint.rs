@@ -0,0 +1,694 @@
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD_NO_PAD, Engine as _};
+use sha2::{Digest, Sha256};
+
+use crate::{FingerprintHash, Key};
+
+// OpenSSH 的 randomart 画布大小和字符表
+const FIELD_WIDTH: usize = 17;
+const FIELD_HEIGHT: usize = 9;
+const AUGMENTATION: &[u8] = b" .o+=*BOX@%&#/^SE";
+
+/// A key digest that can be read aloud, compared by eye or pasted
+pub struct Fingerprint {
+    hash: FingerprintHash,
+    digest: Vec<u8>,
+    key_type: &'static str,
+    bits: usize,
+}
+
+/// Fingerprint a key with SHA-256 (compatible with `ssh-keygen -l`) or Blake3
+pub fn process_fingerprint(key: &Key, hash: FingerprintHash) -> Result<Fingerprint> {
+    let data = key.fingerprint_data()?;
+    let digest = match hash {
+        FingerprintHash::Sha256 => Sha256::digest(&data).to_vec(),
+        FingerprintHash::Blake3 => blake3::hash(&data).as_bytes().to_vec(),
+    };
+    Ok(Fingerprint {
+        hash,
+        digest,
+        key_type: key.type_name(),
+        bits: key.bits(),
+    })
+}
+
+impl Fingerprint {
+    pub fn hex(&self) -> String {
+        self.digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// `SHA256:<base64>` as `ssh-keygen -l` prints it
+    pub fn base64(&self) -> String {
+        format!(
+            "{}:{}",
+            self.hash.to_string().to_uppercase(),
+            STANDARD_NO_PAD.encode(&self.digest)
+        )
+    }
+
+    /// PGP word list, even bytes use the two-syllable list and odd bytes the three-syllable one,
+    /// so swapped or dropped words are noticed over the phone
+    pub fn words(&self) -> String {
+        self.digest
+            .iter()
+            .enumerate()
+            .map(|(i, b)| {
+                if i % 2 == 0 {
+                    EVEN_WORDS[*b as usize]
+                } else {
+                    ODD_WORDS[*b as usize]
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The "drunken bishop" image of `ssh-keygen -lv`
+    pub fn randomart(&self) -> String {
+        let mut field = [[0usize; FIELD_HEIGHT]; FIELD_WIDTH];
+        let end = AUGMENTATION.len() - 1;
+        let (mut x, mut y) = (FIELD_WIDTH / 2, FIELD_HEIGHT / 2);
+        for byte in &self.digest {
+            let mut input = *byte;
+            // 每个字节走 4 步，每步两个 bit 决定斜着往哪个方向走
+            for _ in 0..4 {
+                x = if input & 1 == 1 {
+                    (x + 1).min(FIELD_WIDTH - 1)
+                } else {
+                    x.saturating_sub(1)
+                };
+                y = if input & 2 == 2 {
+                    (y + 1).min(FIELD_HEIGHT - 1)
+                } else {
+                    y.saturating_sub(1)
+                };
+                if field[x][y] < end - 2 {
+                    field[x][y] += 1;
+                }
+                input >>= 2;
+            }
+        }
+        field[FIELD_WIDTH / 2][FIELD_HEIGHT / 2] = end - 1;
+        field[x][y] = end;
+
+        let mut title = format!("[{} {}]", self.key_type, self.bits);
+        if title.len() >= FIELD_WIDTH {
+            title = format!("[{}]", self.key_type);
+        }
+        let mut art = border(&title);
+        for y in 0..FIELD_HEIGHT {
+            art.push('|');
+            for column in &field {
+                art.push(AUGMENTATION[column[y].min(end)] as char);
+            }
+            art.push_str("|\n");
+        }
+        art.push_str(&border(&format!(
+            "[{}]",
+            self.hash.to_string().to_uppercase()
+        )));
+        art
+    }
+}
+
+fn border(label: &str) -> String {
+    let left = FIELD_WIDTH.saturating_sub(label.len()) / 2;
+    let right = FIELD_WIDTH.saturating_sub(left + label.len());
+    format!("+{}{}{}+\n", "-".repeat(left), label, "-".repeat(right))
+}
+
+const EVEN_WORDS: [&str; 256] = [
+    "aardvark",
+    "absurd",
+    "accrue",
+    "acme",
+    "adrift",
+    "adult",
+    "afflict",
+    "ahead",
+    "aimless",
+    "Algol",
+    "allow",
+    "alone",
+    "ammo",
+    "ancient",
+    "apple",
+    "artist",
+    "assume",
+    "Athens",
+    "atlas",
+    "Aztec",
+    "baboon",
+    "backfield",
+    "backward",
+    "banjo",
+    "beaming",
+    "bedlamp",
+    "beehive",
+    "beeswax",
+    "befriend",
+    "Belfast",
+    "berserk",
+    "billiard",
+    "bison",
+    "blackjack",
+    "blockade",
+    "blowtorch",
+    "bluebird",
+    "bombast",
+    "bookshelf",
+    "brackish",
+    "breadline",
+    "breakup",
+    "brickyard",
+    "briefcase",
+    "Burbank",
+    "button",
+    "buzzard",
+    "cement",
+    "chairlift",
+    "chatter",
+    "checkup",
+    "chisel",
+    "choking",
+    "chopper",
+    "Christmas",
+    "clamshell",
+    "classic",
+    "classroom",
+    "cleanup",
+    "clockwork",
+    "cobra",
+    "commence",
+    "concert",
+    "cowbell",
+    "crackdown",
+    "cranky",
+    "crowfoot",
+    "crucial",
+    "crumpled",
+    "crusade",
+    "cubic",
+    "dashboard",
+    "deadbolt",
+    "deckhand",
+    "dogsled",
+    "dragnet",
+    "drainage",
+    "dreadful",
+    "drifter",
+    "dropper",
+    "drumbeat",
+    "drunken",
+    "Dupont",
+    "dwelling",
+    "eating",
+    "edict",
+    "egghead",
+    "eightball",
+    "endorse",
+    "endow",
+    "enlist",
+    "erase",
+    "escape",
+    "exceed",
+    "eyeglass",
+    "eyetooth",
+    "facial",
+    "fallout",
+    "flagpole",
+    "flatfoot",
+    "flytrap",
+    "fracture",
+    "framework",
+    "freedom",
+    "frighten",
+    "gazelle",
+    "Geiger",
+    "glitter",
+    "glucose",
+    "goggles",
+    "goldfish",
+    "gremlin",
+    "guidance",
+    "hamlet",
+    "highchair",
+    "hockey",
+    "indoors",
+    "indulge",
+    "inverse",
+    "involve",
+    "island",
+    "jawbone",
+    "keyboard",
+    "kickoff",
+    "kiwi",
+    "klaxon",
+    "locale",
+    "lockup",
+    "merit",
+    "minnow",
+    "miser",
+    "Mohawk",
+    "mural",
+    "music",
+    "necklace",
+    "Neptune",
+    "newborn",
+    "nightbird",
+    "Oakland",
+    "obtuse",
+    "offload",
+    "optic",
+    "orca",
+    "payday",
+    "peachy",
+    "pheasant",
+    "physique",
+    "playhouse",
+    "Pluto",
+    "preclude",
+    "prefer",
+    "preshrunk",
+    "printer",
+    "prowler",
+    "pupil",
+    "puppy",
+    "python",
+    "quadrant",
+    "quiver",
+    "quota",
+    "ragtime",
+    "ratchet",
+    "rebirth",
+    "reform",
+    "regain",
+    "reindeer",
+    "rematch",
+    "repay",
+    "retouch",
+    "revenge",
+    "reward",
+    "rhythm",
+    "ribcage",
+    "ringbolt",
+    "robust",
+    "rocker",
+    "ruffled",
+    "sailboat",
+    "sawdust",
+    "scallion",
+    "scenic",
+    "scorecard",
+    "Scotland",
+    "seabird",
+    "select",
+    "sentence",
+    "shadow",
+    "shamrock",
+    "showgirl",
+    "skullcap",
+    "skydive",
+    "slingshot",
+    "slowdown",
+    "snapline",
+    "snapshot",
+    "snowcap",
+    "snowslide",
+    "solo",
+    "southward",
+    "soybean",
+    "spaniel",
+    "spearhead",
+    "spellbind",
+    "spheroid",
+    "spigot",
+    "spindle",
+    "spyglass",
+    "stagehand",
+    "stagnate",
+    "stairway",
+    "standard",
+    "stapler",
+    "steamship",
+    "sterling",
+    "stockman",
+    "stopwatch",
+    "stormy",
+    "sugar",
+    "surmount",
+    "suspense",
+    "sweatband",
+    "swelter",
+    "tactics",
+    "talon",
+    "tapeworm",
+    "tempest",
+    "tiger",
+    "tissue",
+    "tonic",
+    "topmost",
+    "tracker",
+    "transit",
+    "trauma",
+    "treadmill",
+    "Trojan",
+    "trouble",
+    "tumor",
+    "tunnel",
+    "tycoon",
+    "uncut",
+    "unearth",
+    "unwind",
+    "uproot",
+    "upset",
+    "upshot",
+    "vapor",
+    "village",
+    "virus",
+    "Vulcan",
+    "waffle",
+    "wallet",
+    "watchword",
+    "wayside",
+    "willow",
+    "woodlark",
+    "Zulu",
+];
+
+const ODD_WORDS: [&str; 256] = [
+    "adroitness",
+    "adviser",
+    "aftermath",
+    "aggregate",
+    "alkali",
+    "almighty",
+    "amulet",
+    "amusement",
+    "antenna",
+    "applicant",
+    "Apollo",
+    "armistice",
+    "article",
+    "asteroid",
+    "Atlantic",
+    "atmosphere",
+    "autopsy",
+    "Babylon",
+    "backwater",
+    "barbecue",
+    "belowground",
+    "bifocals",
+    "bodyguard",
+    "bookseller",
+    "borderline",
+    "bottomless",
+    "Bradbury",
+    "bravado",
+    "Brazilian",
+    "breakaway",
+    "Burlington",
+    "businessman",
+    "butterfat",
+    "Camelot",
+    "candidate",
+    "cannonball",
+    "Capricorn",
+    "caravan",
+    "caretaker",
+    "celebrate",
+    "cellulose",
+    "certify",
+    "chambermaid",
+    "Cherokee",
+    "Chicago",
+    "clergyman",
+    "coherence",
+    "combustion",
+    "commando",
+    "company",
+    "component",
+    "concurrent",
+    "confidence",
+    "conformist",
+    "congregate",
+    "consensus",
+    "consulting",
+    "corporate",
+    "corrosion",
+    "councilman",
+    "crossover",
+    "crucifix",
+    "cumbersome",
+    "customer",
+    "Dakota",
+    "decadence",
+    "December",
+    "decimal",
+    "designing",
+    "detector",
+    "detergent",
+    "determine",
+    "dictator",
+    "dinosaur",
+    "direction",
+    "disable",
+    "disbelief",
+    "disruptive",
+    "distortion",
+    "document",
+    "embezzle",
+    "enchanting",
+    "enrollment",
+    "enterprise",
+    "equation",
+    "equipment",
+    "escapade",
+    "Eskimo",
+    "everyday",
+    "examine",
+    "existence",
+    "exodus",
+    "fascinate",
+    "filament",
+    "finicky",
+    "forever",
+    "fortitude",
+    "frequency",
+    "gadgetry",
+    "Galveston",
+    "getaway",
+    "glossary",
+    "gossamer",
+    "graduate",
+    "gravity",
+    "guitarist",
+    "hamburger",
+    "Hamilton",
+    "handiwork",
+    "hazardous",
+    "headwaters",
+    "hemisphere",
+    "hesitate",
+    "hideaway",
+    "holiness",
+    "hurricane",
+    "hydraulic",
+    "impartial",
+    "impetus",
+    "inception",
+    "indigo",
+    "inertia",
+    "infancy",
+    "inferno",
+    "informant",
+    "insincere",
+    "insurgent",
+    "integrate",
+    "intention",
+    "inventive",
+    "Istanbul",
+    "Jamaica",
+    "Jupiter",
+    "leprosy",
+    "letterhead",
+    "liberty",
+    "maritime",
+    "matchmaker",
+    "maverick",
+    "Medusa",
+    "megaton",
+    "microscope",
+    "microwave",
+    "midsummer",
+    "millionaire",
+    "miracle",
+    "misnomer",
+    "molasses",
+    "molecule",
+    "Montana",
+    "monument",
+    "mosquito",
+    "narrative",
+    "nebula",
+    "newsletter",
+    "Norwegian",
+    "October",
+    "Ohio",
+    "onlooker",
+    "opulent",
+    "Orlando",
+    "outfielder",
+    "Pacific",
+    "pandemic",
+    "Pandora",
+    "paperweight",
+    "paragon",
+    "paragraph",
+    "paramount",
+    "passenger",
+    "pedigree",
+    "Pegasus",
+    "penetrate",
+    "perceptive",
+    "performance",
+    "pharmacy",
+    "phonetic",
+    "photograph",
+    "pioneer",
+    "pocketful",
+    "politeness",
+    "positive",
+    "potato",
+    "processor",
+    "provincial",
+    "proximate",
+    "puberty",
+    "publisher",
+    "pyramid",
+    "quantity",
+    "racketeer",
+    "rebellion",
+    "recipe",
+    "recover",
+    "repellent",
+    "replica",
+    "reproduce",
+    "resistor",
+    "responsive",
+    "retraction",
+    "retrieval",
+    "retrospect",
+    "revenue",
+    "revival",
+    "revolver",
+    "sandalwood",
+    "sardonic",
+    "Saturday",
+    "savagery",
+    "scavenger",
+    "sensation",
+    "sociable",
+    "souvenir",
+    "specialist",
+    "speculate",
+    "stethoscope",
+    "stupendous",
+    "supportive",
+    "surrender",
+    "suspicious",
+    "sympathy",
+    "tambourine",
+    "telephone",
+    "therapist",
+    "tobacco",
+    "tolerance",
+    "tomorrow",
+    "torpedo",
+    "tradition",
+    "travesty",
+    "trombonist",
+    "truncated",
+    "typewriter",
+    "ultimate",
+    "undaunted",
+    "underfoot",
+    "unicorn",
+    "unify",
+    "universe",
+    "unravel",
+    "upcoming",
+    "vacancy",
+    "vagabond",
+    "vertigo",
+    "Virginia",
+    "visitor",
+    "vocalist",
+    "voyager",
+    "warranty",
+    "Waterloo",
+    "whimsical",
+    "Wichita",
+    "Wilmington",
+    "Wyoming",
+    "yesteryear",
+    "Yucatan",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_words() {
+        // PGP word list 的示例
+        let fingerprint = Fingerprint {
+            hash: FingerprintHash::Sha256,
+            digest: hex_literal("E58294F2E9A227486E8B061B31CC528FD7FA3F19"),
+            key_type: "ED25519",
+            bits: 256,
+        };
+        assert_eq!(
+            fingerprint.words(),
+            "topmost Istanbul Pluto vagabond treadmill Pacific brackish dictator goldfish Medusa \
+             afflict bravado chatter revolver Dupont midsummer stopwatch whimsical cowbell bottomless"
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_ssh_keygen() -> Result<()> {
+        // ssh-keygen -lv -E sha256 的输出
+        let key = Key::decode(
+            b"ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIG7I1PHvLNe3gmOzlg6DYkgst/cBfPrEepz0XbMuKWbg\n",
+            None,
+            false,
+        )?;
+        let fingerprint = process_fingerprint(&key, FingerprintHash::Sha256)?;
+        assert_eq!(
+            fingerprint.base64(),
+            "SHA256:EsgpQcrO3ShmY4LYIVQ27anvfYv9NhUuoewzzOvH8uI"
+        );
+        let art = [
+            "+--[ED25519 256]--+",
+            "| o++.            |",
+            "|o..o.+           |",
+            "|o.o = o          |",
+            "|=o.ooo .   . .   |",
+            "|+O.o... S . o .  |",
+            "|= o.   . o . o   |",
+            "|    .   + . o    |",
+            "|     .. oX =     |",
+            "|    .. o+E%o.    |",
+            "+----[SHA256]-----+",
+        ];
+        assert_eq!(fingerprint.randomart(), art.join("\n") + "\n");
+        Ok(())
+    }
+
+    fn hex_literal(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+}
@@ -12,7 +12,6 @@ use std::time::{SystemTime, UNIX_EPOCH};
 /// 2. 将payload转换成json之后使用base64加密
 /// 3. 使用.将header和payload和secret秘钥连接起来 使用定义的加密算法加密后再使用base64生成签名
 /// 4. 最后使用3个base64的字符串使用.拼接起来
-
 pub fn process_gen_jwt(
     header: JWTHeader,
     payload: JWTPayload,
@@ -1,11 +1,18 @@
 use anyhow::Ok;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::{thread_rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 
-const UPPER: &[u8] = b"ABCDEFGHIJKLMNPQRSTUVWXYZ";
-const LOWER: &[u8] = b"abcdefghjkmnopqrstuvwxyz";
-const NUMBER: &[u8] = b"123456789";
-const SYMBOL: &[u8] = b"!\"#$%&'()*+,-./:;<=>?@[]^_{|}~";
+pub(crate) const UPPER: &[u8] = b"ABCDEFGHIJKLMNPQRSTUVWXYZ";
+pub(crate) const LOWER: &[u8] = b"abcdefghjkmnopqrstuvwxyz";
+pub(crate) const NUMBER: &[u8] = b"123456789";
+pub(crate) const SYMBOL: &[u8] = b"!\"#$%&'()*+,-./:;<=>?@[]^_{|}~";
+
+// 派生密码的 KDF 参数是固定的，修改之后所有站点的密码都会变化
+const DERIVE_DOMAIN: &[u8] = b"rcli/genpass/derive/v1";
+const DERIVE_M_COST: u32 = 19 * 1024;
+const DERIVE_T_COST: u32 = 2;
+const DERIVE_P_COST: u32 = 1;
 
 // 避免参数和数据结构过分绑定
 pub fn process_genpass(
@@ -16,43 +23,151 @@ pub fn process_genpass(
     symbol: bool,
 ) -> anyhow::Result<String> {
     let mut rng = thread_rng();
+    generate_password(&mut rng, length, upper, lower, number, symbol)
+}
+
+/// Derive a site password from the master secret.
+///
+/// The master secret is stretched with Argon2id, salted by site, user and counter,
+/// and the result seeds the same generator `process_genpass` uses, so the same
+/// inputs always reproduce the same password.
+#[allow(clippy::too_many_arguments)]
+pub fn process_derive_pass(
+    master: &[u8],
+    site: &str,
+    user: &str,
+    counter: u32,
+    length: u8,
+    upper: bool,
+    lower: bool,
+    number: bool,
+    symbol: bool,
+) -> anyhow::Result<String> {
+    let site = site.trim().to_lowercase();
+    // 每个字段都带上长度，避免 ("ab", "c") 和 ("a", "bc") 得到同样的 salt
+    let mut salt = DERIVE_DOMAIN.to_vec();
+    for field in [site.as_bytes(), user.as_bytes()] {
+        salt.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        salt.extend_from_slice(field);
+    }
+    salt.extend_from_slice(&counter.to_be_bytes());
+
+    let params = Params::new(DERIVE_M_COST, DERIVE_T_COST, DERIVE_P_COST, Some(32))
+        .map_err(|e| anyhow::anyhow!("invalid argon2 params: {}", e))?;
+    let mut seed = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(master, &salt, &mut seed)
+        .map_err(|e| anyhow::anyhow!("failed to derive password: {}", e))?;
+
+    // ChaCha20 的输出流是稳定的，不会随着 rand 的版本变化
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    generate_password(&mut rng, length, upper, lower, number, symbol)
+}
+
+fn generate_password(
+    rng: &mut impl RngCore,
+    length: u8,
+    upper: bool,
+    lower: bool,
+    number: bool,
+    symbol: bool,
+) -> anyhow::Result<String> {
     let mut password = Vec::new();
     let mut chars = Vec::new();
 
     if upper {
         // 对大大写的 O 和 0 在视觉上引发歧义的去除
         chars.extend_from_slice(UPPER);
-        password.push(*UPPER.choose(&mut rng).expect("UPPER won't be empty"));
+        password.push(choose(rng, UPPER));
     }
     if lower {
         // 这里去除了小写 i 和小写的 l 避免视觉歧义
         chars.extend_from_slice(LOWER);
-        password.push(*LOWER.choose(&mut rng).expect("LOWER won't be empty"));
+        password.push(choose(rng, LOWER));
     }
 
     if number {
         chars.extend_from_slice(NUMBER);
-        password.push(*NUMBER.choose(&mut rng).expect("NUMBER won't be empty"));
+        password.push(choose(rng, NUMBER));
     }
 
     if symbol {
         chars.extend_from_slice(SYMBOL);
 
-        password.push(*SYMBOL.choose(&mut rng).expect("SYMBOL won't be empty"));
+        password.push(choose(rng, SYMBOL));
+    }
+
+    if chars.is_empty() {
+        return Err(anyhow::anyhow!("at least one character class is required"));
+    }
+    if (length as usize) < password.len() {
+        return Err(anyhow::anyhow!(
+            "length must be at least {} to include every character class",
+            password.len()
+        ));
     }
 
     // usize -> u8
     for _ in 0..(length - password.len() as u8) {
-        let c = chars
-            .choose(&mut rng)
-            .expect("chars won't be empty in this context");
         // 解引用的内容就会进行拷贝 u8 是支持拷贝的数据类型
-        password.push(*c);
+        password.push(choose(rng, &chars));
     }
 
-    password.shuffle(&mut rng);
+    shuffle(rng, &mut password);
 
     let password = String::from_utf8(password)?;
 
     Ok(password)
 }
+
+/// Uniformly pick an index below `n` using rejection sampling.
+///
+/// Only `next_u32` is used, so a seeded rng always maps to the same picks.
+pub(crate) fn pick_index(rng: &mut impl RngCore, n: usize) -> usize {
+    let n = n as u32;
+    let zone = u32::MAX - (u32::MAX % n);
+    loop {
+        let v = rng.next_u32();
+        if v < zone {
+            return (v % n) as usize;
+        }
+    }
+}
+
+pub(crate) fn choose(rng: &mut impl RngCore, chars: &[u8]) -> u8 {
+    chars[pick_index(rng, chars.len())]
+}
+
+pub(crate) fn shuffle<T>(rng: &mut impl RngCore, items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+        let j = pick_index(rng, i + 1);
+        items.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    fn derive(site: &str, counter: u32) -> Result<String> {
+        process_derive_pass(
+            b"master", site, "alice", counter, 16, true, true, true, true,
+        )
+    }
+
+    #[test]
+    fn test_derive_pass_is_deterministic() -> Result<()> {
+        let a = derive("example.com", 1)?;
+        let b = derive("Example.com ", 1)?;
+        let c = derive("example.com", 2)?;
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a.len(), 16);
+        assert!(a.bytes().any(|c| UPPER.contains(&c)));
+        assert!(a.bytes().any(|c| LOWER.contains(&c)));
+        assert!(a.bytes().any(|c| NUMBER.contains(&c)));
+        assert!(a.bytes().any(|c| SYMBOL.contains(&c)));
+        Ok(())
+    }
+}
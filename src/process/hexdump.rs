@@ -0,0 +1,241 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use anyhow::Result;
+
+/// Layout and window of a hexdump
+#[derive(Debug, Clone)]
+pub struct HexdumpOptions {
+    /// bytes per line
+    pub width: usize,
+    /// bytes per group of hex digits, 0 puts the whole line in one group
+    pub group: usize,
+    /// skip this many bytes of the input
+    pub offset: u64,
+    /// stop after this many bytes
+    pub length: Option<u64>,
+    /// uppercase hex digits
+    pub upper: bool,
+}
+
+impl Default for HexdumpOptions {
+    fn default() -> Self {
+        // 和 xxd 的默认输出一样
+        Self {
+            width: 16,
+            group: 2,
+            offset: 0,
+            length: None,
+            upper: false,
+        }
+    }
+}
+
+/// Write an `xxd`-style dump: offset, grouped hex bytes and printable ascii
+pub fn process_hexdump(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    opts: &HexdumpOptions,
+) -> Result<()> {
+    if opts.width == 0 {
+        return Err(anyhow::anyhow!("width must be greater than 0"));
+    }
+    let skipped = io::copy(&mut reader.take(opts.offset), &mut io::sink())?;
+    if skipped < opts.offset {
+        return Err(anyhow::anyhow!(
+            "offset {} is past the end of the input ({} bytes)",
+            opts.offset,
+            skipped
+        ));
+    }
+    let mut reader = reader.take(opts.length.unwrap_or(u64::MAX));
+
+    let mut line = vec![0u8; opts.width];
+    let mut offset = opts.offset;
+    loop {
+        let n = read_full(&mut reader, &mut line)?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(format_line(offset, &line[..n], opts).as_bytes())?;
+        offset += n as u64;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Turn a hexdump back into binary, plain hex lines without an offset are accepted too
+///
+/// Offsets are relative to the first line, so a dump of a window reverses to just that window.
+pub fn process_hexdump_reverse(reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+    let mut base = None;
+    let mut written = 0u64;
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let invalid = |msg: &str| anyhow::anyhow!("line {}: {}", i + 1, msg);
+        let (offset, hex) = match line.split_once(':') {
+            Some((offset, rest)) => {
+                let offset = u64::from_str_radix(offset.trim(), 16)
+                    .map_err(|_| invalid("invalid offset"))?;
+                // 十六进制和 ascii 两栏之间至少隔两个空格
+                let rest = rest.strip_prefix(' ').unwrap_or(rest);
+                let hex = rest.split("  ").next().unwrap_or_default();
+                (Some(offset), hex)
+            }
+            None => (None, line.as_str()),
+        };
+        let bytes = decode_hex(hex).ok_or_else(|| invalid("invalid hex bytes"))?;
+
+        if let Some(offset) = offset {
+            let offset = offset
+                .checked_sub(*base.get_or_insert(offset))
+                .filter(|offset| *offset >= written)
+                .ok_or_else(|| invalid("offset goes backwards"))?;
+            // 中间跳过的部分用 0 填充
+            io::copy(&mut io::repeat(0).take(offset - written), writer)?;
+            written = offset;
+        }
+        writer.write_all(&bytes)?;
+        written += bytes.len() as u64;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Canonical hexdump of the first `max` bytes with one byte per group
+pub(crate) fn hexdump_preview(data: &[u8], max: usize) -> String {
+    let opts = HexdumpOptions {
+        group: 1,
+        ..Default::default()
+    };
+    let mut out = String::new();
+    for (i, line) in data[..data.len().min(max)].chunks(opts.width).enumerate() {
+        out.push_str(&format_line((i * opts.width) as u64, line, &opts));
+    }
+    if data.len() > max {
+        out.push_str(&format!("... {} more bytes\n", data.len() - max));
+    }
+    out
+}
+
+fn format_line(offset: u64, line: &[u8], opts: &HexdumpOptions) -> String {
+    let group = if opts.group == 0 {
+        opts.width
+    } else {
+        opts.group
+    };
+    let mut hex = String::new();
+    for i in 0..opts.width {
+        if i > 0 && i.is_multiple_of(group) {
+            hex.push(' ');
+        }
+        // 最后一行不满时用空格补齐，让 ascii 那一栏对齐
+        match line.get(i) {
+            Some(b) if opts.upper => hex.push_str(&format!("{:02X}", b)),
+            Some(b) => hex.push_str(&format!("{:02x}", b)),
+            None => hex.push_str("  "),
+        }
+    }
+    let ascii: String = line
+        .iter()
+        .map(|b| {
+            if b.is_ascii_graphic() || *b == b' ' {
+                *b as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    format!("{:08x}: {}  {}\n", offset, hex, ascii)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = hex.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+fn read_full(reader: &mut dyn Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dump(data: &[u8], opts: &HexdumpOptions) -> Result<String> {
+        let mut out = Vec::new();
+        process_hexdump(&mut &data[..], &mut out, opts)?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[test]
+    fn test_hexdump_layout() -> Result<()> {
+        let data = b"hello, rcli hexdump\n";
+        assert_eq!(
+            dump(data, &Default::default())?,
+            "00000000: 6865 6c6c 6f2c 2072 636c 6920 6865 7864  hello, rcli hexd\n\
+             00000010: 756d 700a                                ump.\n"
+        );
+        let opts = HexdumpOptions {
+            width: 4,
+            group: 1,
+            offset: 7,
+            length: Some(6),
+            upper: true,
+        };
+        assert_eq!(
+            dump(data, &opts)?,
+            "00000007: 72 63 6C 69  rcli\n0000000b: 20 68         h\n"
+        );
+        assert!(dump(
+            data,
+            &HexdumpOptions {
+                offset: 100,
+                ..Default::default()
+            }
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hexdump_reverse() -> Result<()> {
+        let data: Vec<u8> = (0..=255u8).chain(*b"  tail  ").collect();
+        for opts in [
+            HexdumpOptions::default(),
+            HexdumpOptions {
+                width: 7,
+                group: 0,
+                offset: 3,
+                length: Some(200),
+                upper: true,
+            },
+        ] {
+            let text = dump(&data, &opts)?;
+            let mut reversed = Vec::new();
+            process_hexdump_reverse(&mut text.as_bytes(), &mut reversed)?;
+            let start = opts.offset as usize;
+            let end = opts.length.map_or(data.len(), |l| start + l as usize);
+            assert_eq!(reversed, &data[start..end]);
+        }
+
+        let mut reversed = Vec::new();
+        process_hexdump_reverse(&mut "6869\n0a".as_bytes(), &mut reversed)?;
+        assert_eq!(reversed, b"hi\n");
+        Ok(())
+    }
+}
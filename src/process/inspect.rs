@@ -0,0 +1,367 @@
+use std::{fmt, io::Read};
+
+use anyhow::Result;
+use serde_json::Value;
+
+use super::hexdump::hexdump_preview;
+use crate::{
+    process_codec_decode, process_decode, process_decompress, Base64Format, CodecFormat,
+    CodecOptions, CompressFormat,
+};
+
+// 避免把普通单词和数字误判成 base64、hex
+const MIN_BASE64_LEN: usize = 8;
+const MIN_HEX_LEN: usize = 8;
+const LONG_BASE64_LEN: usize = 16;
+// base64_confidence 至少要这么多分
+const MIN_BASE64_CONFIDENCE: u32 = 2;
+
+/// One decoded layer: the encoding that was peeled off and the sizes before and after
+#[derive(Debug)]
+pub struct InspectLayer {
+    pub encoding: &'static str,
+    pub input_len: usize,
+    pub output_len: usize,
+}
+
+/// What's left after every recognised layer has been decoded
+#[derive(Debug)]
+pub enum InspectContent {
+    Json(Value),
+    Jwt { header: Value, payload: Value },
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+#[derive(Debug)]
+pub struct InspectReport {
+    pub layers: Vec<InspectLayer>,
+    pub content: InspectContent,
+    pub preview: usize,
+}
+
+/// Guess the encoding of the data and decode layer by layer until nothing more is recognised
+pub fn process_inspect(
+    reader: &mut dyn Read,
+    max_layers: usize,
+    preview: usize,
+) -> Result<InspectReport> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut layers = Vec::new();
+    while layers.len() < max_layers {
+        let Some((encoding, decoded)) = decode_layer(&data) else {
+            break;
+        };
+        layers.push(InspectLayer {
+            encoding,
+            input_len: data.len(),
+            output_len: decoded.len(),
+        });
+        data = decoded;
+    }
+
+    let content = match String::from_utf8(data) {
+        Ok(text) => {
+            if let Some((header, payload)) = parse_jwt(text.trim()) {
+                InspectContent::Jwt { header, payload }
+            } else if let Ok(json) = serde_json::from_str(&text) {
+                InspectContent::Json(json)
+            } else {
+                InspectContent::Text(text)
+            }
+        }
+        Err(e) => InspectContent::Binary(e.into_bytes()),
+    };
+    Ok(InspectReport {
+        layers,
+        content,
+        preview,
+    })
+}
+
+/// Try every known encoding in order, the first one that decodes wins
+fn decode_layer(data: &[u8]) -> Option<(&'static str, Vec<u8>)> {
+    // 魔数匹配但解压失败的，继续当作文本来判断
+    if let Some(format) = compress_format(data) {
+        let mut decompressed = Vec::new();
+        if process_decompress(&mut &data[..], &mut decompressed, format, false).is_ok() {
+            return Some((format.into(), decompressed));
+        }
+    }
+
+    let text = std::str::from_utf8(data).ok()?.trim();
+    // JWT 和 JSON 已经是最终的结果了，不再继续解码
+    if text.is_empty() || parse_jwt(text).is_some() || serde_json::from_str::<Value>(text).is_ok() {
+        return None;
+    }
+    let compact: String = text.split_whitespace().collect();
+    // 纯数字多半是年份、日期之类的十进制数
+    let is_hex = compact.len() >= MIN_HEX_LEN
+        && compact.len().is_multiple_of(2)
+        && compact.bytes().all(|c| c.is_ascii_hexdigit())
+        && !compact.bytes().all(|c| c.is_ascii_digit());
+    if is_hex {
+        return codec_decode(&compact, CodecFormat::Hex).map(|d| ("hex", d));
+    }
+    // base64 只可能按行折断，行内有空格的是普通文本
+    if compact.len() < MIN_BASE64_LEN || text.lines().any(|l| l.trim().contains([' ', '\t'])) {
+        return None;
+    }
+    let standard = compact
+        .bytes()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, b'+' | b'/' | b'='));
+    let url_safe = compact
+        .bytes()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, b'-' | b'_' | b'='));
+    let encoding = match (standard, url_safe) {
+        (true, _) => "base64",
+        (false, true) => "base64url",
+        _ => return None,
+    };
+    // = 只能出现在末尾，最多两个，有填充时总长度是 4 的倍数；不填充时余 1 的长度不可能出现
+    let data_len = compact.trim_end_matches('=').len();
+    let padding = compact.len() - data_len;
+    if padding > 2
+        || compact[..data_len].contains('=')
+        || (padding > 0 && !compact.len().is_multiple_of(4))
+        || data_len % 4 == 1
+    {
+        return None;
+    }
+    let mut decoded = Vec::new();
+    process_decode(&mut compact.as_bytes(), &mut decoded, Base64Format::Auto).ok()?;
+    if base64_confidence(&compact, padding, &decoded) < MIN_BASE64_CONFIDENCE {
+        return None;
+    }
+    Some((encoding, decoded))
+}
+
+/// How likely `compact` is base64 rather than an ordinary word, judged by padding,
+/// alphabet, length and whether the decoded bytes mean anything
+fn base64_confidence(compact: &str, padding: usize, decoded: &[u8]) -> u32 {
+    let has = |f: fn(&u8) -> bool| compact.as_bytes().iter().any(f);
+    let mut score = 0;
+    // 有 = 填充基本就能确定
+    if padding > 0 {
+        score += 2;
+    }
+    // 普通单词里很少有 + 和 /，- 和 _ 在标识符里太常见，不算分
+    score += u32::from(compact.contains('+')) + u32::from(compact.contains('/'));
+    if has(u8::is_ascii_uppercase) && has(u8::is_ascii_lowercase) && has(u8::is_ascii_digit) {
+        score += 1;
+    }
+    if compact.len() >= LONG_BASE64_LEN {
+        score += 1;
+    }
+    if is_printable(decoded) || compress_format(decoded).is_some() {
+        score += 1;
+    }
+    score
+}
+
+fn is_printable(data: &[u8]) -> bool {
+    std::str::from_utf8(data).is_ok_and(|text| {
+        text.chars()
+            .all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'))
+    })
+}
+
+/// Compression formats with a recognisable header, brotli has none
+fn compress_format(data: &[u8]) -> Option<CompressFormat> {
+    if data.starts_with(&[0x1f, 0x8b]) {
+        Some(CompressFormat::Gzip)
+    } else if data.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Some(CompressFormat::Zstd)
+    } else if data.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+        Some(CompressFormat::Xz)
+    } else if is_zlib_header(data) {
+        Some(CompressFormat::Zlib)
+    } else {
+        None
+    }
+}
+
+fn is_zlib_header(data: &[u8]) -> bool {
+    match data {
+        // deflate，没有预设字典，并且校验位正确
+        [cmf, flg, ..] => {
+            cmf & 0x0f == 8
+                && flg & 0x20 == 0
+                && (*cmf as u16 * 256 + *flg as u16).is_multiple_of(31)
+        }
+        _ => false,
+    }
+}
+
+fn codec_decode(text: &str, format: CodecFormat) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    process_codec_decode(
+        &mut text.as_bytes(),
+        &mut decoded,
+        format,
+        &CodecOptions::default(),
+    )
+    .ok()?;
+    Some(decoded)
+}
+
+fn parse_jwt(text: &str) -> Option<(Value, Value)> {
+    let parts: Vec<&str> = text.split('.').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let decode = |part: &str| -> Option<Value> {
+        let mut json = Vec::new();
+        process_decode(&mut part.as_bytes(), &mut json, Base64Format::Auto).ok()?;
+        serde_json::from_slice(&json).ok()
+    };
+    let header = decode(parts[0])?;
+    header.get("alg")?;
+    Some((header, decode(parts[1])?))
+}
+
+impl fmt::Display for InspectReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, layer) in self.layers.iter().enumerate() {
+            writeln!(
+                f,
+                "layer {}: {} ({} -> {} bytes)",
+                i + 1,
+                layer.encoding,
+                layer.input_len,
+                layer.output_len
+            )?;
+        }
+        let pretty = |v: &Value| serde_json::to_string_pretty(v).unwrap_or_else(|_| v.to_string());
+        match &self.content {
+            InspectContent::Json(json) => writeln!(f, "json:\n{}", pretty(json)),
+            InspectContent::Jwt { header, payload } => writeln!(
+                f,
+                "jwt header:\n{}\njwt payload:\n{}",
+                pretty(header),
+                pretty(payload)
+            ),
+            InspectContent::Text(text) => writeln!(f, "text:\n{}", text),
+            InspectContent::Binary(data) => write!(
+                f,
+                "binary ({} bytes):\n{}",
+                data.len(),
+                hexdump_preview(data, self.preview)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    #[test]
+    fn test_inspect_base64_gzip_json() -> Result<()> {
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(br#"{"name": "rcli"}"#)?;
+        let gz = gz.finish()?;
+        let mut encoded = Vec::new();
+        crate::process_encode(
+            &mut &gz[..],
+            &mut encoded,
+            Base64Format::Standard,
+            Default::default(),
+        )?;
+
+        let report = process_inspect(&mut &encoded[..], 8, 256)?;
+        let layers: Vec<_> = report.layers.iter().map(|l| l.encoding).collect();
+        assert_eq!(layers, ["base64", "gzip"]);
+        assert!(matches!(report.content, InspectContent::Json(ref v) if v["name"] == "rcli"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_zstd_xz() -> Result<()> {
+        for (format, name) in [(CompressFormat::Zstd, "zstd"), (CompressFormat::Xz, "xz")] {
+            let mut compressed = Vec::new();
+            crate::process_compress(&mut &b"hello"[..], &mut compressed, format, None, true)?;
+            let report = process_inspect(&mut &compressed[..], 8, 256)?;
+            let layers: Vec<_> = report.layers.iter().map(|l| l.encoding).collect();
+            assert_eq!(layers, ["base64", name]);
+            assert!(matches!(report.content, InspectContent::Text(ref t) if t == "hello"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_jwt_and_binary() -> Result<()> {
+        let jwt = "eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiJhY21lIn0.c2ln";
+        let report = process_inspect(&mut jwt.as_bytes(), 8, 256)?;
+        assert!(report.layers.is_empty());
+        assert!(
+            matches!(report.content, InspectContent::Jwt { ref payload, .. } if payload["sub"] == "acme")
+        );
+
+        let report = process_inspect(&mut "00ff10ab".as_bytes(), 8, 256)?;
+        assert_eq!(report.layers[0].encoding, "hex");
+        assert!(
+            matches!(report.content, InspectContent::Binary(ref d) if d == &[0, 0xff, 0x10, 0xab])
+        );
+        assert!(report.to_string().contains("00000000: 00 ff 10 ab"));
+
+        let report = process_inspect(&mut "x marks the spot".as_bytes(), 8, 256)?;
+        assert!(report.layers.is_empty());
+        assert!(matches!(report.content, InspectContent::Text(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_plain_words() -> Result<()> {
+        // 字符集都合法，但长度不对或者解出来是乱码
+        for word in [
+            "HelloWorld",
+            "username1",
+            "username",
+            "password",
+            "cafe",
+            "deadbeef1",
+            "src/main",
+            "Password123",
+            "ThisIsALongVariableName",
+        ] {
+            let report = process_inspect(&mut word.as_bytes(), 8, 256)?;
+            assert!(report.layers.is_empty(), "{} was decoded", word);
+            assert!(matches!(report.content, InspectContent::Text(ref t) if t == word));
+        }
+        // 年份、日期会被当成 JSON 数字，但不能按 hex 解码
+        for number in ["2024", "20241019"] {
+            assert!(process_inspect(&mut number.as_bytes(), 8, 256)?
+                .layers
+                .is_empty());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_base64_variants() -> Result<()> {
+        // 有填充、没填充
+        for input in ["aGVsbG8gd29ybGQ=", "aGVsbG8gd29ybGQ"] {
+            let report = process_inspect(&mut input.as_bytes(), 8, 256)?;
+            assert_eq!(report.layers[0].encoding, "base64");
+            assert!(matches!(report.content, InspectContent::Text(ref t) if t == "hello world"));
+        }
+
+        // 解出来是二进制也要剥掉这一层，用 hexdump 展示
+        let report = process_inspect(&mut "AAECA//+\n".as_bytes(), 8, 256)?;
+        assert_eq!(report.layers[0].encoding, "base64");
+        assert!(
+            matches!(report.content, InspectContent::Binary(ref d) if d == &[0, 1, 2, 3, 0xff, 0xfe])
+        );
+        assert!(report.to_string().contains("00000000: 00 01 02 03 ff fe"));
+
+        let report = process_inspect(&mut "-vv8_f7_AAECAwQFBgcICQ".as_bytes(), 8, 256)?;
+        assert_eq!(report.layers[0].encoding, "base64url");
+        assert!(matches!(report.content, InspectContent::Binary(ref d) if d.len() == 16));
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+use std::{
+    cell::Cell,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    process_encode_generated, process_fingerprint, process_generate, process_key_convert,
+    read_key_passphrase, write_private, FingerprintHash, Key, KeyFormat, TextSignFormat,
+};
+
+const KEYRING_ENV: &str = "RCLI_KEYRING";
+// 指纹前缀太短容易撞，至少 8 个十六进制字符
+const MIN_FINGERPRINT_PREFIX: usize = 8;
+
+/// A directory of named keys, `<name>.key` next to `<name>.json` metadata
+pub struct Keyring {
+    dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyringEntry {
+    pub name: String,
+    pub algorithm: String,
+    pub private: bool,
+    pub created_at: String,
+    pub fingerprint: String,
+    #[serde(default)]
+    pub comment: String,
+}
+
+/// Resolve `--key`: an existing file wins, otherwise a keyring name or fingerprint prefix.
+/// The format falls back to the keyring entry's algorithm, then blake3
+pub fn process_resolve_key(
+    key: &str,
+    format: Option<TextSignFormat>,
+) -> Result<(String, TextSignFormat)> {
+    if Path::new(key).is_file() {
+        return Ok((key.to_string(), format.unwrap_or(TextSignFormat::Blake3)));
+    }
+    let keyring = Keyring::open()?;
+    let entry = keyring
+        .find(key)
+        .map_err(|e| anyhow::anyhow!("{} is not a key file: {}", key, e))?;
+    let format = match format {
+        Some(format) => format,
+        None => entry.algorithm.parse()?,
+    };
+    Ok((keyring.key_path(&entry.name).display().to_string(), format))
+}
+
+impl Keyring {
+    /// RCLI_KEYRING, or ~/.rcli/keyring
+    pub fn open() -> Result<Self> {
+        let dir = match std::env::var_os(KEYRING_ENV) {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                let home = std::env::var_os("HOME")
+                    .or_else(|| std::env::var_os("USERPROFILE"))
+                    .ok_or_else(|| anyhow::anyhow!("can't find home, set {}", KEYRING_ENV))?;
+                PathBuf::from(home).join(".rcli").join("keyring")
+            }
+        };
+        Ok(Self::at(dir))
+    }
+
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn list(&self) -> Result<Vec<KeyringEntry>> {
+        if !self.dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut entries = Vec::new();
+        for file in fs::read_dir(&self.dir)? {
+            let path = file?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                entries.push(serde_json::from_str(&fs::read_to_string(path)?)?);
+            }
+        }
+        entries.sort_by(|a: &KeyringEntry, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Look up by exact name first, then by a unique fingerprint prefix
+    pub fn find(&self, query: &str) -> Result<KeyringEntry> {
+        let entries = self.list()?;
+        if let Some(entry) = entries.iter().find(|e| e.name == query) {
+            return Ok(entry.clone());
+        }
+        if query.len() < MIN_FINGERPRINT_PREFIX || !query.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow::anyhow!("no key named {} in the keyring", query));
+        }
+        let prefix = query.to_lowercase();
+        let mut found = entries
+            .into_iter()
+            .filter(|e| e.fingerprint.starts_with(&prefix));
+        match (found.next(), found.next()) {
+            (Some(entry), None) => Ok(entry),
+            (Some(_), Some(_)) => Err(anyhow::anyhow!("fingerprint {} is ambiguous", query)),
+            (None, _) => Err(anyhow::anyhow!("no key with fingerprint {}", query)),
+        }
+    }
+
+    pub fn key_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.key", name))
+    }
+
+    fn meta_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", name))
+    }
+
+    pub fn generate(
+        &self,
+        name: &str,
+        format: TextSignFormat,
+        comment: &str,
+        passphrase: Option<&str>,
+    ) -> Result<KeyringEntry> {
+        let keys = process_generate(format)?;
+        let key = Key::decode(&keys[0], Some(format), true)?;
+        let key_format = match key {
+            Key::Symmetric(_) => KeyFormat::Raw,
+            _ => KeyFormat::Pem,
+        };
+        let encoded = process_encode_generated(format, keys, key_format, passphrase)?;
+        self.add(name, format, &key, &encoded[0], comment)
+    }
+
+    /// Import a key file, encrypted keys are stored as they are, everything else as PEM
+    /// (raw for symmetric keys)
+    pub fn import(
+        &self,
+        name: &str,
+        data: &[u8],
+        format: Option<TextSignFormat>,
+        raw_public: bool,
+        comment: &str,
+    ) -> Result<KeyringEntry> {
+        let encrypted = Cell::new(false);
+        let key = Key::decode_with(data, format, !raw_public, &|| {
+            encrypted.set(true);
+            read_key_passphrase()
+        })?;
+        let format = match (format, &key) {
+            (Some(format), _) => format,
+            (None, Key::Symmetric(_)) => {
+                return Err(anyhow::anyhow!("symmetric keys need --format"))
+            }
+            (None, Key::Ed25519Secret(_) | Key::Ed25519Public(_)) => TextSignFormat::Ed25519,
+            (None, Key::P256Secret(_) | Key::P256Public(_)) => TextSignFormat::EcdsaP256,
+            (None, Key::P384Secret(_) | Key::P384Public(_)) => TextSignFormat::EcdsaP384,
+            (None, Key::RsaSecret(_) | Key::RsaPublic(_)) => TextSignFormat::RsaPss,
+        };
+        let stored = match &key {
+            _ if encrypted.get() => data.to_vec(),
+            Key::Symmetric(_) => key.encode(KeyFormat::Raw)?,
+            _ => key.encode(KeyFormat::Pem)?,
+        };
+        self.add(name, format, &key, &stored, comment)
+    }
+
+    pub fn export(
+        &self,
+        name: &str,
+        to: KeyFormat,
+        public: bool,
+        passphrase: Option<&str>,
+    ) -> Result<Vec<u8>> {
+        let entry = self.find(name)?;
+        let data = fs::read(self.key_path(&entry.name))?;
+        process_key_convert(
+            &data,
+            to,
+            public,
+            Some(entry.algorithm.parse()?),
+            !entry.private,
+            passphrase,
+        )
+    }
+
+    pub fn delete(&self, name: &str) -> Result<KeyringEntry> {
+        let entry = self.find(name)?;
+        fs::remove_file(self.key_path(&entry.name))?;
+        fs::remove_file(self.meta_path(&entry.name))?;
+        Ok(entry)
+    }
+
+    fn add(
+        &self,
+        name: &str,
+        format: TextSignFormat,
+        key: &Key,
+        data: &[u8],
+        comment: &str,
+    ) -> Result<KeyringEntry> {
+        verify_name(name)?;
+        if self.meta_path(name).exists() {
+            return Err(anyhow::anyhow!("key {} already exists", name));
+        }
+        let entry = KeyringEntry {
+            name: name.to_string(),
+            algorithm: format.to_string(),
+            private: key.is_private(),
+            created_at: format_timestamp(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()),
+            fingerprint: process_fingerprint(key, FingerprintHash::Sha256)?.hex(),
+            comment: comment.to_string(),
+        };
+        self.create_dir()?;
+        write_private(self.key_path(name), data)?;
+        fs::write(
+            self.meta_path(name),
+            serde_json::to_string_pretty(&entry)? + "\n",
+        )?;
+        Ok(entry)
+    }
+
+    fn create_dir(&self) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&self.dir, fs::Permissions::from_mode(0o700))?;
+        }
+        Ok(())
+    }
+}
+
+// 名字直接拿来当文件名，不能带路径分隔符
+fn verify_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "invalid key name {}, use letters, digits, '-', '_' and '.'",
+            name
+        ))
+    }
+}
+
+/// RFC 3339 in UTC, days to civil date from Howard Hinnant's algorithm
+fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    let secs = secs % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyring() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("rcli-keyring-{}", std::process::id()));
+        let keyring = Keyring::at(&dir);
+        let alice = keyring.generate("alice", TextSignFormat::Ed25519, "release key", None)?;
+        assert!(alice.private);
+        assert!(keyring
+            .generate("alice", TextSignFormat::Ed25519, "", None)
+            .is_err());
+        assert!(keyring
+            .generate("../evil", TextSignFormat::Blake3, "", None)
+            .is_err());
+
+        // 导出公钥再导入，指纹不变
+        let public = keyring.export("alice", KeyFormat::OpenSsh, true, None)?;
+        let bob = keyring.import("bob", &public, None, false, "")?;
+        assert!(!bob.private);
+        assert_eq!(bob.algorithm, "ed25519");
+        assert_eq!(bob.fingerprint, alice.fingerprint);
+
+        keyring.generate("mac", TextSignFormat::HmacSha256, "", None)?;
+        let names: Vec<_> = keyring.list()?.into_iter().map(|e| e.name).collect();
+        assert_eq!(names, ["alice", "bob", "mac"]);
+        // 同一指纹有两个 key，前缀不唯一
+        assert!(keyring.find(&alice.fingerprint[..8]).is_err());
+        keyring.delete("bob")?;
+        assert_eq!(keyring.find(&alice.fingerprint[..8])?.name, "alice");
+        assert!(keyring.find("abc").is_err());
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_792_403_708), "2026-10-19T09:55:08Z");
+    }
+}
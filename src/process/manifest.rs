@@ -0,0 +1,266 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs::{self, File},
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::Result;
+
+const HEADER: &str = "# rcli manifest v1";
+const DIR_PREFIX: &str = "# dir: ";
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub blake3: String,
+}
+
+/// Directories recorded in the manifest and one entry per file, sorted by path
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    pub dirs: Vec<String>,
+    pub entries: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Default)]
+pub struct ManifestReport {
+    pub tampered: Vec<String>,
+    pub missing: Vec<String>,
+    pub extra: Vec<String>,
+}
+
+/// Hash every file under `paths` (files or directories relative to `root`),
+/// skipping `skip` so the manifest and its signature can live in the tree
+pub fn process_manifest_build(root: &Path, paths: &[String], skip: &[PathBuf]) -> Result<Manifest> {
+    let skip = canonical(skip);
+    let mut manifest = Manifest::default();
+    let mut files = BTreeMap::new();
+    for path in paths {
+        let full = root.join(path);
+        if full.is_dir() {
+            manifest.dirs.push(normalize(path));
+            for file in walk(&full)? {
+                if !skip.contains(&fs::canonicalize(&file)?) {
+                    files.insert(relative(root, &file)?, file);
+                }
+            }
+        } else {
+            files.insert(normalize(path), full);
+        }
+    }
+    for (path, file) in files {
+        let (size, blake3) = hash_file(&file)?;
+        manifest.entries.push(ManifestEntry { path, size, blake3 });
+    }
+    Ok(manifest)
+}
+
+/// Compare the files under `root` with the manifest
+pub fn process_manifest_check(
+    root: &Path,
+    manifest: &Manifest,
+    skip: &[PathBuf],
+) -> Result<ManifestReport> {
+    let mut report = ManifestReport::default();
+    for entry in &manifest.entries {
+        let file = root.join(&entry.path);
+        if !file.is_file() {
+            report.missing.push(entry.path.clone());
+        } else if hash_file(&file)? != (entry.size, entry.blake3.clone()) {
+            report.tampered.push(entry.path.clone());
+        }
+    }
+    // 只在签名时遍历过的目录里找多出来的文件
+    let skip = canonical(skip);
+    for dir in &manifest.dirs {
+        let dir = root.join(dir);
+        if !dir.is_dir() {
+            continue;
+        }
+        for file in walk(&dir)? {
+            let path = relative(root, &file)?;
+            if !skip.contains(&fs::canonicalize(&file)?)
+                && !manifest.entries.iter().any(|e| e.path == path)
+            {
+                report.extra.push(path);
+            }
+        }
+    }
+    report.extra.sort();
+    Ok(report)
+}
+
+impl ManifestReport {
+    pub fn is_ok(&self) -> bool {
+        self.tampered.is_empty() && self.missing.is_empty() && self.extra.is_empty()
+    }
+}
+
+impl std::fmt::Display for Manifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut s = String::new();
+        writeln!(s, "{}", HEADER)?;
+        for dir in &self.dirs {
+            writeln!(s, "{}{}", DIR_PREFIX, dir)?;
+        }
+        // 和 b3sum 类似，路径放最后，可以包含空格
+        for entry in &self.entries {
+            writeln!(s, "{}  {}  {}", entry.blake3, entry.size, entry.path)?;
+        }
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for Manifest {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(anyhow::anyhow!("not an rcli manifest"));
+        }
+        let mut manifest = Manifest::default();
+        for line in lines {
+            if let Some(dir) = line.strip_prefix(DIR_PREFIX) {
+                manifest.dirs.push(verify_relative(dir)?);
+                continue;
+            }
+            let mut parts = line.splitn(3, "  ");
+            let (Some(blake3), Some(size), Some(path)) = (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(anyhow::anyhow!("invalid manifest line: {}", line));
+            };
+            manifest.entries.push(ManifestEntry {
+                path: verify_relative(path)?,
+                size: size.parse()?,
+                blake3: blake3.to_string(),
+            });
+        }
+        Ok(manifest)
+    }
+}
+
+// 校验时路径会拼到 root 上，不能跳出 root
+fn verify_relative(path: &str) -> Result<String> {
+    let valid = !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if valid {
+        Ok(path.to_string())
+    } else {
+        Err(anyhow::anyhow!("manifest path {} escapes the root", path))
+    }
+}
+
+// 大小取实际哈希过的字节数，文件中途被改也不会和摘要对不上
+fn hash_file(path: &Path) -> Result<(u64, String)> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(File::open(path)?)?;
+    Ok((hasher.count(), hasher.finalize().to_hex().to_string()))
+}
+
+/// All regular files under `dir`, recursively. Symlinked files are hashed through the
+/// link, symlinked directories are skipped so a link cycle can't recurse forever
+fn walk(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            files.extend(walk(&path)?);
+        } else if file_type.is_file() || (file_type.is_symlink() && path.is_file()) {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn relative(root: &Path, file: &Path) -> Result<String> {
+    let path = file.strip_prefix(root)?;
+    Ok(normalize(&path.to_string_lossy()))
+}
+
+// manifest 里统一用 / 分隔，去掉开头的 ./
+fn normalize(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let path = path.trim_start_matches("./").trim_end_matches('/');
+    path.to_string()
+}
+
+fn canonical(paths: &[PathBuf]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .filter_map(|p| fs::canonicalize(p).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_build_check() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("rcli-manifest-{}", std::process::id()));
+        fs::create_dir_all(root.join("dist/sub"))?;
+        fs::write(root.join("dist/a.txt"), "a")?;
+        fs::write(root.join("dist/sub/b c.txt"), "bc")?;
+        fs::write(root.join("README"), "readme")?;
+
+        let manifest = process_manifest_build(&root, &["dist".into(), "README".into()], &[])?;
+        let text = manifest.to_string();
+        assert!(text.contains("  2  dist/sub/b c.txt\n"));
+        let manifest: Manifest = text.parse()?;
+        assert_eq!(manifest.dirs, ["dist"]);
+        assert_eq!(manifest.entries.len(), 3);
+        assert!(process_manifest_check(&root, &manifest, &[])?.is_ok());
+
+        fs::write(root.join("dist/a.txt"), "A")?;
+        fs::remove_file(root.join("README"))?;
+        fs::write(root.join("dist/sub/new"), "")?;
+        let report = process_manifest_check(&root, &manifest, &[])?;
+        assert_eq!(report.tampered, ["dist/a.txt"]);
+        assert_eq!(report.missing, ["README"]);
+        assert_eq!(report.extra, ["dist/sub/new"]);
+
+        fs::remove_dir_all(root)?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_manifest_symlinks() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("rcli-manifest-link-{}", std::process::id()));
+        fs::create_dir_all(root.join("dist"))?;
+        fs::write(root.join("dist/a.txt"), "a")?;
+        // 指回上层的目录链接会形成环，文件链接照常记录
+        std::os::unix::fs::symlink("..", root.join("dist/loop"))?;
+        std::os::unix::fs::symlink("a.txt", root.join("dist/b.txt"))?;
+
+        let manifest = process_manifest_build(&root, &["dist".into()], &[])?;
+        let paths: Vec<_> = manifest.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["dist/a.txt", "dist/b.txt"]);
+        assert!(process_manifest_check(&root, &manifest, &[])?.is_ok());
+
+        fs::remove_dir_all(root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_manifest_rejects_escaping_paths() {
+        let line = |path: &str| format!("{}\n{}  1  {}\n", HEADER, "0".repeat(64), path);
+        for path in ["../../etc/passwd", "/etc/passwd", "a/../../b", ""] {
+            assert!(line(path).parse::<Manifest>().is_err(), "{}", path);
+        }
+        assert!(format!("{}\n{}..\n", HEADER, DIR_PREFIX)
+            .parse::<Manifest>()
+            .is_err());
+        assert!(line("dist/a.txt").parse::<Manifest>().is_ok());
+        assert!(format!("{}\n{}.\n", HEADER, DIR_PREFIX)
+            .parse::<Manifest>()
+            .is_ok());
+    }
+}
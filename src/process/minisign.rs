@@ -0,0 +1,320 @@
+use std::io::{self, Read};
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use blake2::{digest::consts::U32, Blake2b, Blake2b512, Digest};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use rand::{rngs::OsRng, RngCore};
+
+pub(crate) const UNTRUSTED_PREFIX: &str = "untrusted comment: ";
+const TRUSTED_PREFIX: &str = "trusted comment: ";
+const SIG_ALG: &[u8] = b"Ed";
+// 新版 minisign 默认先对文件做 BLAKE2b-512 再签名
+const SIG_ALG_HASHED: &[u8] = b"ED";
+const KDF_ALG: &[u8] = b"Sc";
+const KDF_NONE: &[u8] = &[0, 0];
+const CHK_ALG: &[u8] = b"B2";
+// 和 minisign 一样用 libsodium 的 scrypt SENSITIVE 参数
+const OPSLIMIT: u64 = 33_554_432;
+const MEMLIMIT: u64 = 1_073_741_824;
+// 读 key 时拒绝比 SENSITIVE 还大的参数，避免恶意 key 耗尽内存和 CPU
+const MAX_OPSLIMIT: u64 = OPSLIMIT;
+const MAX_MEMLIMIT: u64 = MEMLIMIT;
+// sig_alg, kdf_alg, chk_alg, salt, opslimit, memlimit, key id, secret key, checksum
+const SECRET_KEY_LEN: usize = 2 + 2 + 2 + 32 + 8 + 8 + 8 + 64 + 32;
+const PUBLIC_KEY_LEN: usize = 2 + 8 + 32;
+const SIGNATURE_LEN: usize = 2 + 8 + 64;
+
+/// Generate a minisign key pair, returns the secret and public key file contents
+pub fn process_minisign_keygen(passphrase: Option<&str>) -> Result<(String, String)> {
+    keygen_with(passphrase, OPSLIMIT, MEMLIMIT)
+}
+
+/// Sign with a minisign secret key, `passphrase` is only asked for if the key is encrypted
+pub fn process_minisign_sign(
+    reader: &mut dyn Read,
+    secret_key: &str,
+    passphrase: &dyn Fn() -> Result<String>,
+    trusted_comment: &str,
+) -> Result<String> {
+    if trusted_comment.contains(['\r', '\n']) {
+        return Err(anyhow::anyhow!("trusted comment must be a single line"));
+    }
+    let (key_id, key) = decode_secret_key(secret_key, passphrase)?;
+    let mut hasher = Blake2b512::new();
+    io::copy(reader, &mut hasher)?;
+    let signature = key.sign(&hasher.finalize());
+
+    let mut sig = Vec::with_capacity(SIGNATURE_LEN);
+    sig.extend_from_slice(SIG_ALG_HASHED);
+    sig.extend_from_slice(&key_id);
+    sig.extend_from_slice(&signature.to_bytes());
+    // 全局签名覆盖文件签名和 trusted comment，防止 comment 被篡改
+    let global = key.sign(&[&signature.to_bytes()[..], trusted_comment.as_bytes()].concat());
+    Ok(format!(
+        "{}signature from minisign secret key\n{}\n{}{}\n{}\n",
+        UNTRUSTED_PREFIX,
+        STANDARD.encode(sig),
+        TRUSTED_PREFIX,
+        trusted_comment,
+        STANDARD.encode(global.to_bytes())
+    ))
+}
+
+/// Verify a minisign signature, returns the trusted comment if it is valid
+///
+/// `public_key` is either a public key file or the bare base64 key
+pub fn process_minisign_verify(
+    reader: &mut dyn Read,
+    public_key: &str,
+    signature: &str,
+) -> Result<Option<String>> {
+    let pk = decode_base64(public_key, PUBLIC_KEY_LEN, "public key")?;
+    if &pk[..2] != SIG_ALG {
+        return Err(anyhow::anyhow!("unsupported minisign public key"));
+    }
+    let key = VerifyingKey::from_bytes(pk[10..].try_into()?)?;
+
+    let mut lines = signature.lines();
+    let (Some(untrusted), Some(sig), Some(trusted), Some(global)) =
+        (lines.next(), lines.next(), lines.next(), lines.next())
+    else {
+        return Err(anyhow::anyhow!("invalid minisign signature file"));
+    };
+    let (Some(_), Some(trusted)) = (
+        untrusted.strip_prefix(UNTRUSTED_PREFIX),
+        trusted.strip_prefix(TRUSTED_PREFIX),
+    ) else {
+        return Err(anyhow::anyhow!("invalid minisign signature file"));
+    };
+    let sig = decode_base64(sig, SIGNATURE_LEN, "signature")?;
+    if sig[2..10] != pk[2..10] {
+        return Err(anyhow::anyhow!(
+            "signature key id {} doesn't match public key {}",
+            key_id_hex(&sig[2..10]),
+            key_id_hex(&pk[2..10])
+        ));
+    }
+    let signature = Signature::from_bytes(sig[10..].try_into()?);
+    let global = Signature::from_bytes(STANDARD.decode(global.trim())?.as_slice().try_into()?);
+
+    let message = match &sig[..2] {
+        alg if alg == SIG_ALG_HASHED => {
+            let mut hasher = Blake2b512::new();
+            io::copy(reader, &mut hasher)?;
+            hasher.finalize().to_vec()
+        }
+        alg if alg == SIG_ALG => {
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf)?;
+            buf
+        }
+        _ => return Err(anyhow::anyhow!("unsupported minisign signature algorithm")),
+    };
+    let global_message = [&sig[10..], trusted.as_bytes()].concat();
+    let valid = key.verify_strict(&message, &signature).is_ok()
+        && key.verify_strict(&global_message, &global).is_ok();
+    Ok(valid.then(|| trusted.to_string()))
+}
+
+fn keygen_with(passphrase: Option<&str>, opslimit: u64, memlimit: u64) -> Result<(String, String)> {
+    let key = SigningKey::generate(&mut OsRng);
+    let mut key_id = [0u8; 8];
+    OsRng.fill_bytes(&mut key_id);
+
+    let mut keynum_sk = Vec::with_capacity(104);
+    keynum_sk.extend_from_slice(&key_id);
+    keynum_sk.extend_from_slice(&key.to_keypair_bytes());
+    keynum_sk.extend_from_slice(&checksum(&key_id, &key));
+
+    let mut sk = Vec::with_capacity(SECRET_KEY_LEN);
+    sk.extend_from_slice(SIG_ALG);
+    match passphrase {
+        Some(passphrase) => {
+            let mut salt = [0u8; 32];
+            OsRng.fill_bytes(&mut salt);
+            xor_stream(&mut keynum_sk, passphrase, &salt, opslimit, memlimit)?;
+            sk.extend_from_slice(KDF_ALG);
+            sk.extend_from_slice(CHK_ALG);
+            sk.extend_from_slice(&salt);
+            sk.extend_from_slice(&opslimit.to_le_bytes());
+            sk.extend_from_slice(&memlimit.to_le_bytes());
+        }
+        None => {
+            sk.extend_from_slice(KDF_NONE);
+            sk.extend_from_slice(CHK_ALG);
+            sk.extend_from_slice(&[0u8; 48]);
+        }
+    }
+    sk.extend_from_slice(&keynum_sk);
+
+    let mut pk = Vec::with_capacity(PUBLIC_KEY_LEN);
+    pk.extend_from_slice(SIG_ALG);
+    pk.extend_from_slice(&key_id);
+    pk.extend_from_slice(key.verifying_key().as_bytes());
+
+    let comment = if passphrase.is_some() {
+        "minisign encrypted secret key"
+    } else {
+        "minisign secret key"
+    };
+    Ok((
+        format!("{}{}\n{}\n", UNTRUSTED_PREFIX, comment, STANDARD.encode(sk)),
+        format!(
+            "{}minisign public key {}\n{}\n",
+            UNTRUSTED_PREFIX,
+            key_id_hex(&key_id),
+            STANDARD.encode(pk)
+        ),
+    ))
+}
+
+fn decode_secret_key(
+    content: &str,
+    passphrase: &dyn Fn() -> Result<String>,
+) -> Result<([u8; 8], SigningKey)> {
+    let sk = decode_base64(content, SECRET_KEY_LEN, "secret key")?;
+    if &sk[..2] != SIG_ALG || &sk[4..6] != CHK_ALG {
+        return Err(anyhow::anyhow!("unsupported minisign secret key"));
+    }
+    let mut keynum_sk = sk[54..].to_vec();
+    match &sk[2..4] {
+        alg if alg == KDF_ALG => {
+            let opslimit = u64::from_le_bytes(sk[38..46].try_into()?);
+            let memlimit = u64::from_le_bytes(sk[46..54].try_into()?);
+            if opslimit > MAX_OPSLIMIT {
+                return Err(anyhow::anyhow!("scrypt opslimit {} is too large", opslimit));
+            }
+            if memlimit > MAX_MEMLIMIT {
+                return Err(anyhow::anyhow!("scrypt memlimit {} is too large", memlimit));
+            }
+            xor_stream(
+                &mut keynum_sk,
+                &passphrase()?,
+                &sk[6..38],
+                opslimit,
+                memlimit,
+            )?;
+        }
+        alg if alg == KDF_NONE => {}
+        _ => return Err(anyhow::anyhow!("unsupported minisign key derivation")),
+    }
+    let key_id: [u8; 8] = keynum_sk[..8].try_into()?;
+    let key = SigningKey::from_keypair_bytes(keynum_sk[8..72].try_into()?)
+        .map_err(|_| anyhow::anyhow!("wrong passphrase or corrupted secret key"))?;
+    if checksum(&key_id, &key)[..] != keynum_sk[72..] {
+        return Err(anyhow::anyhow!("wrong passphrase or corrupted secret key"));
+    }
+    Ok((key_id, key))
+}
+
+fn checksum(key_id: &[u8], key: &SigningKey) -> [u8; 32] {
+    let mut hasher = Blake2b::<U32>::new();
+    hasher.update(SIG_ALG);
+    hasher.update(key_id);
+    hasher.update(key.to_keypair_bytes());
+    hasher.finalize().into()
+}
+
+// libsodium 的 crypto_pwhash_scryptsalsa208sha256，参数由 opslimit/memlimit 推出来
+fn xor_stream(
+    data: &mut [u8],
+    passphrase: &str,
+    salt: &[u8],
+    opslimit: u64,
+    memlimit: u64,
+) -> Result<()> {
+    let opslimit = opslimit.max(32768);
+    let r = 8u64;
+    let pick_log_n = |max_n: u64| (1..63).find(|n| 1u64 << n > max_n / 2).unwrap_or(63);
+    let (log_n, p) = if opslimit < memlimit / 32 {
+        (pick_log_n(opslimit / (r * 4)), 1)
+    } else {
+        let log_n = pick_log_n(memlimit / (r * 128));
+        let max_rp = ((opslimit / 4) >> log_n).min(0x3fff_ffff);
+        (log_n, max_rp / r)
+    };
+    let params = scrypt::Params::new(log_n as u8, r as u32, p as u32, 64)?;
+    let mut stream = vec![0u8; data.len()];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut stream)?;
+    data.iter_mut().zip(stream).for_each(|(d, s)| *d ^= s);
+    Ok(())
+}
+
+/// Decode the base64 payload after an optional untrusted comment line
+pub(crate) fn decode_base64(content: &str, len: usize, name: &str) -> Result<Vec<u8>> {
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with(UNTRUSTED_PREFIX))
+        .ok_or_else(|| anyhow::anyhow!("empty {}", name))?;
+    let data = STANDARD.decode(line)?;
+    if data.len() != len {
+        return Err(anyhow::anyhow!("invalid {} length: {}", name, data.len()));
+    }
+    Ok(data)
+}
+
+// minisign 把 key id 当成小端 u64 显示
+fn key_id_hex(key_id: &[u8]) -> String {
+    key_id.iter().rev().map(|b| format!("{:02X}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 另一把 minisign 公钥，key id 不同
+    const OTHER_PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    const MESSAGE: &[u8] = b"test";
+
+    #[test]
+    fn test_minisign_sign_verify() -> Result<()> {
+        let no_passphrase = || -> Result<String> { panic!("key is not encrypted") };
+        let (sk, pk) = process_minisign_keygen(None)?;
+        assert!(pk.starts_with("untrusted comment: minisign public key "));
+        let sig = process_minisign_sign(&mut &MESSAGE[..], &sk, &no_passphrase, "hello")?;
+        let trusted = process_minisign_verify(&mut &MESSAGE[..], &pk, &sig)?;
+        assert_eq!(trusted.as_deref(), Some("hello"));
+        assert!(process_minisign_verify(&mut &b"tampered"[..], &pk, &sig)?.is_none());
+
+        // trusted comment 被改了全局签名就不对了
+        let forged = sig.replace("trusted comment: hello", "trusted comment: hacked");
+        assert!(process_minisign_verify(&mut &MESSAGE[..], &pk, &forged)?.is_none());
+
+        // 只给 base64 公钥也可以，但 key id 要对得上
+        let bare = pk.lines().last().unwrap();
+        assert!(process_minisign_verify(&mut &MESSAGE[..], bare, &sig)?.is_some());
+        assert!(process_minisign_verify(&mut &MESSAGE[..], OTHER_PUBLIC_KEY, &sig).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_minisign_encrypted_key() -> Result<()> {
+        // 用小参数，默认参数要 1 GiB 内存
+        let (sk, pk) = keygen_with(Some("hunter2"), 32768, 16 << 20)?;
+        let sig = process_minisign_sign(&mut &MESSAGE[..], &sk, &|| Ok("hunter2".into()), "t")?;
+        assert!(process_minisign_verify(&mut &MESSAGE[..], &pk, &sig)?.is_some());
+        assert!(
+            process_minisign_sign(&mut &MESSAGE[..], &sk, &|| Ok("wrong".into()), "t").is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_minisign_rejects_large_kdf_limits() -> Result<()> {
+        let no_passphrase = || -> Result<String> { panic!("limits are checked first") };
+        let (sk, _) = keygen_with(Some("hunter2"), 32768, 16 << 20)?;
+        let sk = STANDARD.decode(sk.lines().nth(1).unwrap())?;
+        // opslimit 在 38..46，memlimit 在 46..54
+        for (at, limit) in [(38, MAX_OPSLIMIT), (46, MAX_MEMLIMIT)] {
+            let mut tampered = sk.clone();
+            tampered[at..at + 8].copy_from_slice(&(limit + 1).to_le_bytes());
+            let tampered = STANDARD.encode(tampered);
+            let err = process_minisign_sign(&mut &MESSAGE[..], &tampered, &no_passphrase, "t")
+                .unwrap_err();
+            assert!(err.to_string().contains("too large"));
+        }
+        Ok(())
+    }
+}
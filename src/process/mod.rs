@@ -1,13 +1,54 @@
+mod age;
 mod b64;
+mod codec;
+mod compress;
 mod csv_convert;
+mod data_uri;
+mod encrypt;
+mod escape;
+mod fingerprint;
 mod gen_jwt;
 mod gen_pass;
+mod hexdump;
 mod http_serve;
+mod inspect;
+mod key;
+mod keyring;
+mod manifest;
+mod minisign;
+mod num;
+mod pass;
+mod pass_modes;
+mod pass_policy;
+mod signify;
 mod text;
 
+pub use age::{load_age_keys, process_age_decrypt, process_age_encrypt, process_age_keygen};
 pub use b64::{process_decode, process_encode};
+pub use codec::{process_codec_decode, process_codec_encode, CodecOptions};
+pub use compress::{process_compress, process_decompress};
 pub use csv_convert::process_csv;
+pub use data_uri::{process_datauri_decode, process_datauri_encode};
+pub use encrypt::{process_text_decrypt, process_text_encrypt, CryptKey};
+pub use escape::{
+    process_html_escape, process_html_unescape, process_unicode_escape, process_unicode_unescape,
+    process_url_decode, process_url_encode,
+};
+pub use fingerprint::{process_fingerprint, Fingerprint};
 pub use gen_jwt::{process_gen_jwt, process_validate_jwt};
-pub use gen_pass::process_genpass;
+pub use gen_pass::{process_derive_pass, process_genpass};
+pub use hexdump::{process_hexdump, process_hexdump_reverse, HexdumpOptions};
 pub use http_serve::process_http_serve;
+pub use inspect::{process_inspect, InspectContent, InspectLayer, InspectReport};
+pub use key::{process_encode_generated, process_key_convert, read_key_passphrase, Key};
+pub use keyring::{process_resolve_key, Keyring, KeyringEntry};
+pub use manifest::{
+    process_manifest_build, process_manifest_check, Manifest, ManifestEntry, ManifestReport,
+};
+pub use minisign::{process_minisign_keygen, process_minisign_sign, process_minisign_verify};
+pub use num::{process_duration, process_radix, process_size};
+pub use pass::{process_pass_breached, process_pass_hash, process_pass_verify};
+pub use pass_modes::{process_genpass_pronounceable, process_genpin};
+pub use pass_policy::{load_pass_policy, process_genpass_policy, CharClass, PasswordPolicy};
+pub use signify::{process_signify_keygen, process_signify_sign, process_signify_verify};
 pub use text::{process_generate, process_text_sign, process_text_verify};
@@ -0,0 +1,359 @@
+use anyhow::Result;
+use num_bigint::{BigInt, Sign};
+
+const SIZE_UNITS: &[(&str, u128)] = &[
+    ("b", 1),
+    ("kb", 1_000),
+    ("mb", 1_000_000),
+    ("gb", 1_000_000_000),
+    ("tb", 1_000_000_000_000),
+    ("pb", 1_000_000_000_000_000),
+    ("eb", 1_000_000_000_000_000_000),
+    ("kib", 1 << 10),
+    ("mib", 1 << 20),
+    ("gib", 1 << 30),
+    ("tib", 1 << 40),
+    ("pib", 1 << 50),
+    ("eib", 1 << 60),
+];
+
+const SI_UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB", "PB", "EB"];
+const IEC_UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+// 时长统一换算成纳秒
+const DURATION_UNITS: &[(&str, u128)] = &[
+    ("w", 7 * 86_400_000_000_000),
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// Convert an integer between radixes, `width` shows the two's complement bit pattern
+///
+/// Without `from` the radix is taken from a `0x`/`0o`/`0b` prefix, defaulting to 10.
+pub fn process_radix(
+    value: &str,
+    from: Option<u32>,
+    to: Option<u32>,
+    width: Option<u32>,
+) -> Result<String> {
+    let value = parse_int(value, from)?;
+    let Some(width) = width else {
+        return Ok(match to {
+            Some(radix) => value.to_str_radix(radix),
+            None => format!(
+                "dec: {}\nhex: {}\noct: {}\nbin: {}",
+                value,
+                prefixed(&value, 16, "0x"),
+                prefixed(&value, 8, "0o"),
+                prefixed(&value, 2, "0b")
+            ),
+        });
+    };
+
+    if width == 0 {
+        return Err(anyhow::anyhow!("width must be greater than 0"));
+    }
+    let modulus = BigInt::from(1) << width;
+    let min = -(BigInt::from(1) << (width - 1));
+    if value < min || value >= modulus {
+        return Err(anyhow::anyhow!("{} doesn't fit in {} bits", value, width));
+    }
+    // 负数加上 2^width 就是补码，反过来最高位是 1 的就是负数
+    let unsigned = if value.sign() == Sign::Minus {
+        &value + &modulus
+    } else {
+        value
+    };
+    let signed = if unsigned.bits() == width as u64 {
+        &unsigned - &modulus
+    } else {
+        unsigned.clone()
+    };
+    let pad = |radix: u32, digits: u32| {
+        format!(
+            "{:0>width$}",
+            unsigned.to_str_radix(radix),
+            width = width.div_ceil(digits) as usize
+        )
+    };
+    Ok(match to {
+        Some(radix) => unsigned.to_str_radix(radix),
+        None => format!(
+            "unsigned: {}\nsigned: {}\nhex: 0x{}\noct: 0o{}\nbin: 0b{}",
+            unsigned,
+            signed,
+            pad(16, 4),
+            unsigned.to_str_radix(8),
+            pad(2, 1)
+        ),
+    })
+}
+
+/// Parse a byte size like `1.5GiB` or `300 MB` and show it in bytes, SI and IEC units
+pub fn process_size(value: &str, to: Option<&str>) -> Result<String> {
+    let (number, unit) = split_unit(value);
+    let unit = if unit.is_empty() { "b" } else { unit };
+    let bytes = parse_decimal(number, size_unit(unit)?)?;
+    if let Some(to) = to {
+        return Ok(format_ratio(bytes, size_unit(to)?));
+    }
+    Ok(format!(
+        "bytes: {}\nsi: {}\niec: {}",
+        bytes,
+        human_size(bytes, 1000, SI_UNITS),
+        human_size(bytes, 1024, IEC_UNITS)
+    ))
+}
+
+/// Parse a duration like `1h30m`, `1.5d` or `250ms`, a bare number is in seconds
+pub fn process_duration(value: &str, to: Option<&str>) -> Result<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(anyhow::anyhow!("empty duration"));
+    }
+    let mut nanos = 0u128;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(end);
+        let unit_end = tail
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_end);
+        let unit = if unit.is_empty() && tail.is_empty() {
+            "s"
+        } else {
+            unit
+        };
+        nanos = nanos
+            .checked_add(parse_decimal(number, duration_unit(unit)?)?)
+            .ok_or_else(|| anyhow::anyhow!("duration is too large"))?;
+        rest = tail.trim_start();
+    }
+    if let Some(to) = to {
+        return Ok(format_ratio(nanos, duration_unit(to)?));
+    }
+    Ok(format!(
+        "seconds: {}\nhuman: {}",
+        format_ratio(nanos, 1_000_000_000),
+        human_duration(nanos)
+    ))
+}
+
+fn parse_int(value: &str, from: Option<u32>) -> Result<BigInt> {
+    let value: String = value.trim().chars().filter(|c| *c != '_').collect();
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, value.strip_prefix('+').unwrap_or(&value)),
+    };
+    let prefix = |p: &str| {
+        digits
+            .strip_prefix(p)
+            .or_else(|| digits.strip_prefix(&p.to_uppercase()))
+    };
+    let (radix, digits) = match from {
+        Some(16) => (16, prefix("0x").unwrap_or(digits)),
+        Some(8) => (8, prefix("0o").unwrap_or(digits)),
+        Some(2) => (2, prefix("0b").unwrap_or(digits)),
+        Some(radix) => (radix, digits),
+        None => match (prefix("0x"), prefix("0o"), prefix("0b")) {
+            (Some(d), _, _) => (16, d),
+            (_, Some(d), _) => (8, d),
+            (_, _, Some(d)) => (2, d),
+            _ => (10, digits),
+        },
+    };
+    if !(2..=36).contains(&radix) {
+        return Err(anyhow::anyhow!("radix must be between 2 and 36"));
+    }
+    let value = BigInt::parse_bytes(digits.as_bytes(), radix)
+        .filter(|_| !digits.starts_with(['+', '-']))
+        .ok_or_else(|| anyhow::anyhow!("invalid base {} number: {}", radix, digits))?;
+    Ok(if negative { -value } else { value })
+}
+
+fn prefixed(value: &BigInt, radix: u32, prefix: &str) -> String {
+    match value.sign() {
+        Sign::Minus => format!("-{}{}", prefix, (-value).to_str_radix(radix)),
+        _ => format!("{}{}", prefix, value.to_str_radix(radix)),
+    }
+}
+
+/// Split `1.5 GiB` into the number and the unit
+fn split_unit(value: &str) -> (&str, &str) {
+    let value = value.trim();
+    let end = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    (&value[..end], value[end..].trim())
+}
+
+fn size_unit(unit: &str) -> Result<u128> {
+    let unit = unit.to_lowercase();
+    SIZE_UNITS
+        .iter()
+        .find(|(u, _)| *u == unit || u.trim_end_matches('b') == unit)
+        .map(|(_, m)| *m)
+        .ok_or_else(|| anyhow::anyhow!("unknown size unit: {}", unit))
+}
+
+fn duration_unit(unit: &str) -> Result<u128> {
+    let unit = match unit {
+        "sec" | "secs" => "s",
+        "min" | "mins" => "m",
+        unit => unit,
+    };
+    DURATION_UNITS
+        .iter()
+        .find(|(u, _)| *u == unit)
+        .map(|(_, m)| *m)
+        .ok_or_else(|| anyhow::anyhow!("unknown duration unit: {:?}", unit))
+}
+
+/// `number * multiplier` computed exactly on the decimal digits, rounded to the nearest integer
+fn parse_decimal(number: &str, multiplier: u128) -> Result<u128> {
+    let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+    let valid = |s: &str| s.bytes().all(|c| c.is_ascii_digit());
+    if (int.is_empty() && frac.is_empty()) || !valid(int) || !valid(frac) || frac.len() > 18 {
+        return Err(anyhow::anyhow!("invalid number: {:?}", number));
+    }
+    let overflow = || anyhow::anyhow!("number is too large: {}", number);
+    let int: u128 = if int.is_empty() { 0 } else { int.parse()? };
+    let scale = 10u128.pow(frac.len() as u32);
+    let frac: u128 = if frac.is_empty() { 0 } else { frac.parse()? };
+    let whole = int.checked_mul(multiplier).ok_or_else(overflow)?;
+    let part = frac.checked_mul(multiplier).ok_or_else(overflow)?;
+    whole
+        .checked_add((part + scale / 2) / scale)
+        .ok_or_else(overflow)
+}
+
+/// `value / unit` as a decimal without trailing zeros
+fn format_ratio(value: u128, unit: u128) -> String {
+    // 最多保留 9 位小数，只对余数放大，避免溢出；舍入进位加到整数部分
+    let (mut int, rem) = (value / unit, value % unit);
+    let mut frac = (rem * 1_000_000_000 + unit / 2) / unit;
+    if frac == 1_000_000_000 {
+        int += 1;
+        frac = 0;
+    }
+    if frac == 0 {
+        return int.to_string();
+    }
+    let frac = format!("{:09}", frac);
+    format!("{}.{}", int, frac.trim_end_matches('0'))
+}
+
+fn human_size(bytes: u128, base: u128, units: &[&str]) -> String {
+    let mut unit = 0;
+    let mut scale = 1u128;
+    while unit + 1 < units.len() && bytes >= scale * base {
+        scale *= base;
+        unit += 1;
+    }
+    if unit == 0 {
+        return format!("{} {}", bytes, units[0]);
+    }
+    format!("{:.2} {}", bytes as f64 / scale as f64, units[unit])
+}
+
+fn human_duration(nanos: u128) -> String {
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+    let mut rest = nanos;
+    let mut parts = Vec::new();
+    for (unit, size) in DURATION_UNITS {
+        if rest >= *size {
+            parts.push(format!("{}{}", rest / size, unit));
+            rest %= size;
+        }
+    }
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_radix() -> Result<()> {
+        assert_eq!(
+            process_radix("255", None, None, None)?,
+            "dec: 255\nhex: 0xff\noct: 0o377\nbin: 0b11111111"
+        );
+        assert_eq!(process_radix("0xFF_FF", None, Some(10), None)?, "65535");
+        assert_eq!(process_radix("zz", Some(36), Some(2), None)?, "10100001111");
+        let big = "123456789012345678901234567890";
+        let hex = process_radix(big, None, Some(16), None)?;
+        assert_eq!(process_radix(&hex, Some(16), Some(10), None)?, big);
+        assert!(process_radix("12", Some(2), None, None).is_err());
+        assert!(process_radix("--1", None, None, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_twos_complement() -> Result<()> {
+        assert_eq!(
+            process_radix("-1", None, None, Some(8))?,
+            "unsigned: 255\nsigned: -1\nhex: 0xff\noct: 0o377\nbin: 0b11111111"
+        );
+        assert_eq!(
+            process_radix("0x8000", None, None, Some(16))?
+                .lines()
+                .nth(1),
+            Some("signed: -32768")
+        );
+        assert_eq!(process_radix("-2", None, Some(16), Some(32))?, "fffffffe");
+        assert_eq!(
+            process_radix("5", None, None, Some(12))?.lines().last(),
+            Some("bin: 0b000000000101")
+        );
+        assert!(process_radix("-129", None, None, Some(8)).is_err());
+        assert!(process_radix("256", None, None, Some(8)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_and_duration() -> Result<()> {
+        assert_eq!(
+            process_size("1.5GiB", None)?,
+            "bytes: 1610612736\nsi: 1.61 GB\niec: 1.50 GiB"
+        );
+        assert_eq!(process_size("1500 kb", Some("MB"))?, "1.5");
+        assert_eq!(process_size("2048", Some("kib"))?, "2");
+        assert!(process_size("1 parsec", None).is_err());
+
+        assert_eq!(
+            process_duration("1h30m", None)?,
+            "seconds: 5400\nhuman: 1h 30m"
+        );
+        assert_eq!(process_duration("90", Some("m"))?, "1.5");
+        assert_eq!(process_duration("1.5d 250ms", Some("h"))?, "36.000069444");
+        assert!(process_duration("3 fortnights", None).is_err());
+
+        // 刚好差一点到下一个单位，舍入后要进位
+        assert_eq!(
+            process_size(&((1u64 << 60) - 1).to_string(), Some("EiB"))?,
+            "1"
+        );
+        assert_eq!(process_duration("604799999999999ns", Some("w"))?, "1");
+        // 接近 u128::MAX 也不能溢出
+        let max = u128::MAX.to_string();
+        assert_eq!(process_size(&max, Some("b"))?, max);
+        assert!(process_size(&max, Some("kb"))?.starts_with("340282366920938463463374607431768211"));
+        assert!(process_duration("1000000000000000000000w", None).is_ok());
+        assert_eq!(
+            process_duration("1000000000000000000000w", Some("w"))?,
+            "1000000000000000000000"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+use anyhow::Result;
+use argon2::Argon2;
+use password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use pbkdf2::Pbkdf2;
+use ring::digest;
+use scrypt::Scrypt;
+use std::{
+    cmp::Ordering,
+    fs::{self, File},
+    io::{BufRead, BufReader, Seek, SeekFrom},
+    path::Path,
+};
+
+use crate::{PassHashAlgorithm, PassHashParams};
+
+pub fn process_pass_hash(
+    password: &str,
+    algo: PassHashAlgorithm,
+    params: &PassHashParams,
+) -> Result<String> {
+    let password = password.as_bytes();
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = match algo {
+        PassHashAlgorithm::Argon2id => {
+            let default = argon2::Params::default();
+            let p = argon2::Params::new(
+                params.m_cost.unwrap_or(default.m_cost()),
+                params.t_cost.unwrap_or(default.t_cost()),
+                params.p_cost.unwrap_or(default.p_cost()),
+                None,
+            )
+            .map_err(|e| anyhow::anyhow!("invalid argon2 params: {}", e))?;
+            Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, p)
+                .hash_password(password, &salt)
+        }
+        PassHashAlgorithm::Bcrypt => {
+            // bcrypt 用的是自己的 $2b$ 格式，不是 PHC 字符串
+            let cost = params.cost.unwrap_or(bcrypt::DEFAULT_COST);
+            return Ok(bcrypt::hash(password, cost)?);
+        }
+        PassHashAlgorithm::Scrypt => {
+            let p = scrypt::Params::new(
+                params.log_n.unwrap_or(scrypt::Params::RECOMMENDED_LOG_N),
+                params.block_size.unwrap_or(scrypt::Params::RECOMMENDED_R),
+                params.p_cost.unwrap_or(scrypt::Params::RECOMMENDED_P),
+                scrypt::Params::RECOMMENDED_LEN,
+            )
+            .map_err(|e| anyhow::anyhow!("invalid scrypt params: {}", e))?;
+            Scrypt.hash_password_customized(password, None, None, p, &salt)
+        }
+        PassHashAlgorithm::Pbkdf2Sha256 | PassHashAlgorithm::Pbkdf2Sha512 => {
+            let ident = if algo == PassHashAlgorithm::Pbkdf2Sha256 {
+                pbkdf2::Algorithm::Pbkdf2Sha256.ident()
+            } else {
+                pbkdf2::Algorithm::Pbkdf2Sha512.ident()
+            };
+            let default = pbkdf2::Params::default();
+            let p = pbkdf2::Params {
+                rounds: params.rounds.unwrap_or(default.rounds),
+                ..default
+            };
+            Pbkdf2.hash_password_customized(password, Some(ident), None, p, &salt)
+        }
+    }
+    .map_err(|e| anyhow::anyhow!("failed to hash password: {}", e))?;
+
+    Ok(hash.to_string())
+}
+
+pub fn process_pass_verify(password: &str, hash: &str) -> Result<bool> {
+    let password = password.as_bytes();
+    let hash = hash.trim();
+    if hash.starts_with("$2") {
+        return Ok(bcrypt::verify(password, hash)?);
+    }
+
+    let parsed =
+        PasswordHash::new(hash).map_err(|e| anyhow::anyhow!("invalid PHC string: {}", e))?;
+    let verifier: &dyn PasswordVerifier = match parsed.algorithm.as_str() {
+        "argon2id" | "argon2i" | "argon2d" => &Argon2::default(),
+        "scrypt" => &Scrypt,
+        "pbkdf2-sha256" | "pbkdf2-sha512" => &Pbkdf2,
+        alg => return Err(anyhow::anyhow!("unsupported algorithm: {}", alg)),
+    };
+    match verifier.verify_password(password, &parsed) {
+        Ok(()) => Ok(true),
+        Err(password_hash::Error::Password) => Ok(false),
+        Err(e) => Err(anyhow::anyhow!("failed to verify password: {}", e)),
+    }
+}
+
+/// Look up a password in a local breached-password SHA-1 list and return its count.
+///
+/// `db` is either a single file of sorted `HASH:COUNT` lines, or a directory of
+/// k-anonymity range files named by the first 5 hex digits holding `SUFFIX:COUNT` lines.
+pub fn process_pass_breached(password: &str, db: impl AsRef<Path>) -> Result<u64> {
+    let hash = digest::digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, password.as_bytes());
+    let hash: String = hash.as_ref().iter().map(|b| format!("{:02X}", b)).collect();
+    let db = db.as_ref();
+
+    if db.is_dir() {
+        let (prefix, suffix) = hash.split_at(5);
+        let range = [prefix.to_string(), prefix.to_lowercase()]
+            .into_iter()
+            .flat_map(|p| [db.join(&p), db.join(format!("{}.txt", p))])
+            .find(|p| p.is_file());
+        let Some(range) = range else {
+            return Ok(0);
+        };
+        // 单个 range 文件很小，直接读进内存做二分查找
+        let content = fs::read_to_string(range)?;
+        let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+        let count = lines
+            .binary_search_by(|line| compare_entry(line, suffix))
+            .ok()
+            .map(|i| parse_count(lines[i]))
+            .transpose()?;
+        return Ok(count.unwrap_or(0));
+    }
+
+    let mut file = File::open(db)?;
+    Ok(search_sorted_file(&mut file, &hash)?.unwrap_or(0))
+}
+
+/// Binary search a sorted `HASH:COUNT` file by seeking, without loading it into memory
+fn search_sorted_file(file: &mut File, hash: &str) -> Result<Option<u64>> {
+    // lo 始终是某一行的起始位置
+    let (mut lo, mut hi) = (0, file.metadata()?.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let Some((start, line)) = line_at_or_after(file, mid)? else {
+            hi = mid;
+            continue;
+        };
+        if start >= hi {
+            hi = mid;
+            continue;
+        }
+        match compare_entry(line.trim_end(), hash) {
+            Ordering::Equal => return Ok(Some(parse_count(line.trim_end())?)),
+            Ordering::Less => lo = start + line.len() as u64,
+            Ordering::Greater => hi = mid,
+        }
+    }
+    Ok(None)
+}
+
+/// Return the first full line starting at or after `pos`, with its offset
+fn line_at_or_after(file: &mut File, pos: u64) -> Result<Option<(u64, String)>> {
+    let mut start = pos;
+    let mut reader = if pos == 0 {
+        file.seek(SeekFrom::Start(0))?;
+        BufReader::new(&mut *file)
+    } else {
+        // 从 pos - 1 开始跳过当前行的剩余部分
+        file.seek(SeekFrom::Start(pos - 1))?;
+        let mut reader = BufReader::new(&mut *file);
+        let mut skipped = Vec::new();
+        start = pos - 1 + reader.read_until(b'\n', &mut skipped)? as u64;
+        reader
+    };
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some((start, line)))
+}
+
+fn compare_entry(line: &str, hash: &str) -> Ordering {
+    let key = line.split_once(':').map(|(k, _)| k).unwrap_or(line);
+    key.trim().to_uppercase().as_str().cmp(hash)
+}
+
+fn parse_count(line: &str) -> Result<u64> {
+    let count = line
+        .split_once(':')
+        .map(|(_, c)| c.trim())
+        .ok_or_else(|| anyhow::anyhow!("invalid hash line: {}", line))?;
+    Ok(count.parse()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pass_hash_verify() -> Result<()> {
+        // 用最低的代价参数，避免测试太慢
+        let params = PassHashParams {
+            m_cost: Some(1024),
+            t_cost: Some(1),
+            cost: Some(4),
+            log_n: Some(4),
+            rounds: Some(1000),
+            ..Default::default()
+        };
+        for algo in [
+            PassHashAlgorithm::Argon2id,
+            PassHashAlgorithm::Bcrypt,
+            PassHashAlgorithm::Scrypt,
+            PassHashAlgorithm::Pbkdf2Sha256,
+            PassHashAlgorithm::Pbkdf2Sha512,
+        ] {
+            let hash = process_pass_hash("hunter2", algo, &params)?;
+            assert!(process_pass_verify("hunter2", &hash)?, "{}", algo);
+            assert!(!process_pass_verify("hunter3", &hash)?, "{}", algo);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_pass_breached() -> Result<()> {
+        for db in ["fixtures/pwned/hashes.txt", "fixtures/pwned/range"] {
+            assert_eq!(process_pass_breached("password", db)?, 9545824);
+            assert_eq!(process_pass_breached("123456", db)?, 37359195);
+            assert_eq!(process_pass_breached("not-in-the-list", db)?, 0);
+        }
+        assert_eq!(
+            process_pass_breached("hunter2", "fixtures/pwned/hashes.txt")?,
+            28928
+        );
+        assert_eq!(
+            process_pass_breached("dragon", "fixtures/pwned/hashes.txt")?,
+            1017436
+        );
+
+        // 每一行都应该能被二分查找命中
+        let mut file = File::open("fixtures/pwned/hashes.txt")?;
+        for line in fs::read_to_string("fixtures/pwned/hashes.txt")?.lines() {
+            let (hash, count) = line.split_once(':').unwrap();
+            assert_eq!(search_sorted_file(&mut file, hash)?, Some(count.parse()?));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use rand::thread_rng;
+
+use super::gen_pass::{choose, pick_index};
+
+// 去掉了容易混淆或者读音含糊的 c q x l y
+const CONSONANT: &[u8] = b"bdfghjkmnprstvwz";
+const VOWEL: &[u8] = b"aeiou";
+const DIGIT: &[u8] = b"0123456789";
+
+// 超过这个长度就不再枚举所有 PIN，改为只枚举弱 PIN
+const PIN_ENUMERATE_MAX: u8 = 6;
+
+/// Generate a consonant-vowel syllable password and return it with its entropy in bits
+pub fn process_genpass_pronounceable(
+    syllables: u8,
+    digits: u8,
+    capitalize: bool,
+    separator: &str,
+) -> Result<(String, f64)> {
+    if syllables == 0 {
+        return Err(anyhow::anyhow!("at least one syllable is required"));
+    }
+    let mut rng = thread_rng();
+    let mut parts = Vec::with_capacity(syllables as usize + 1);
+    for _ in 0..syllables {
+        let syllable = [choose(&mut rng, CONSONANT), choose(&mut rng, VOWEL)];
+        parts.push(String::from_utf8(syllable.to_vec())?);
+    }
+    if capitalize {
+        parts[0][..1].make_ascii_uppercase();
+    }
+    if digits > 0 {
+        let number: Vec<u8> = (0..digits).map(|_| choose(&mut rng, DIGIT)).collect();
+        parts.push(String::from_utf8(number)?);
+    }
+
+    let entropy = syllables as f64 * ((CONSONANT.len() * VOWEL.len()) as f64).log2()
+        + digits as f64 * (DIGIT.len() as f64).log2();
+    Ok((parts.join(separator), entropy))
+}
+
+/// Generate a numeric PIN that avoids weak patterns, with the entropy left after filtering
+pub fn process_genpin(length: u8) -> Result<(String, f64)> {
+    if !(4..=12).contains(&length) {
+        return Err(anyhow::anyhow!("PIN length must be between 4 and 12"));
+    }
+    let mut rng = thread_rng();
+    let pin = loop {
+        let pin: Vec<u8> = (0..length)
+            .map(|_| pick_index(&mut rng, DIGIT.len()) as u8)
+            .collect();
+        if !is_weak_pin(&pin) {
+            break pin;
+        }
+    };
+
+    let entropy = if length <= PIN_ENUMERATE_MAX {
+        let total = 10u32.pow(length as u32);
+        let mut pin = vec![0u8; length as usize];
+        let strong = (0..total)
+            .filter(|n| {
+                let mut n = *n;
+                for d in pin.iter_mut().rev() {
+                    *d = (n % 10) as u8;
+                    n /= 10;
+                }
+                !is_weak_pin(&pin)
+            })
+            .count();
+        (strong as f64).log2()
+    } else {
+        (10f64.powi(length as i32) - count_weak_pins(length) as f64).log2()
+    };
+
+    let pin = pin.iter().map(|d| (b'0' + d) as char).collect();
+    Ok((pin, entropy))
+}
+
+/// Number of weak PINs of this length, counted from the patterns themselves
+/// since 10^length PINs are too many to check one by one
+fn count_weak_pins(length: u8) -> usize {
+    let length = length as usize;
+    let to_num = |pin: &[u8]| pin.iter().fold(0u64, |acc, d| acc * 10 + *d as u64);
+    let mut weak = HashSet::new();
+    // 重复的块，例如 n = 2 时 block * 10101...
+    for n in (1..=length / 2).filter(|n| length.is_multiple_of(*n)) {
+        let base = 10u64.pow(n as u32);
+        let repeat = (0..length / n).fold(0, |acc, _| acc * base + 1);
+        weak.extend((0..base).map(|block| block * repeat));
+    }
+    // 等差数列
+    for start in 0..10i32 {
+        for step in -9..=9i32 {
+            let pin: Option<Vec<u8>> = (0..length as i32)
+                .map(|i| u8::try_from(start + step * i).ok().filter(|d| *d < 10))
+                .collect();
+            if let Some(pin) = pin {
+                weak.insert(to_num(&pin));
+            }
+        }
+    }
+    // 日期只有 8 位的才有，两位数取 0..=31 已经覆盖了所有日和月
+    if length == 8 {
+        for (a, b, year) in (0..32u64)
+            .flat_map(|a| (0..32u64).flat_map(move |b| (1900..2100u64).map(move |y| (a, b, y))))
+        {
+            for n in [(a * 100 + b) * 10_000 + year, year * 10_000 + a * 100 + b] {
+                let pin: Vec<u8> = format!("{:08}", n).bytes().map(|c| c - b'0').collect();
+                if is_weak_pin(&pin) {
+                    weak.insert(n);
+                }
+            }
+        }
+    }
+    weak.len()
+}
+
+/// Digits that repeat a block, form an arithmetic sequence or read as a date
+pub(crate) fn is_weak_pin(pin: &[u8]) -> bool {
+    is_repeated(pin) || is_sequence(pin) || is_date(pin)
+}
+
+fn is_repeated(pin: &[u8]) -> bool {
+    (1..=pin.len() / 2)
+        .filter(|n| pin.len().is_multiple_of(*n))
+        .any(|n| pin.chunks(n).all(|c| c == &pin[..n]))
+}
+
+fn is_sequence(pin: &[u8]) -> bool {
+    let step = pin[1] as i8 - pin[0] as i8;
+    pin.windows(2).all(|w| w[1] as i8 - w[0] as i8 == step)
+}
+
+fn is_date(pin: &[u8]) -> bool {
+    let num = |s: &[u8]| s.iter().fold(0u32, |acc, d| acc * 10 + *d as u32);
+    let year = |y: u32| (1900..=2099).contains(&y);
+    match pin.len() {
+        4 => {
+            let (a, b) = (num(&pin[..2]), num(&pin[2..]));
+            is_day_month(b, a) || is_day_month(a, b) || year(num(pin))
+        }
+        6 => {
+            let (a, b, c) = (num(&pin[..2]), num(&pin[2..4]), num(&pin[4..]));
+            is_day_month(a, b) || is_day_month(b, a) || is_day_month(c, b)
+        }
+        8 => {
+            let (a, b) = (num(&pin[..2]), num(&pin[2..4]));
+            let (c, d) = (num(&pin[4..6]), num(&pin[6..]));
+            (year(num(&pin[4..])) && (is_day_month(a, b) || is_day_month(b, a)))
+                || (year(num(&pin[..4])) && is_day_month(d, c))
+        }
+        _ => false,
+    }
+}
+
+fn is_day_month(day: u32, month: u32) -> bool {
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 => 29,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weak(pin: &str) -> bool {
+        let pin: Vec<u8> = pin.bytes().map(|b| b - b'0').collect();
+        is_weak_pin(&pin)
+    }
+
+    #[test]
+    fn test_weak_pin() {
+        for pin in [
+            "1111", "1212", "1234", "9876", "2468", "0704", "1987", "311299", "19991231",
+        ] {
+            assert!(weak(pin), "{}", pin);
+        }
+        for pin in ["7291", "4830", "583920", "61940372"] {
+            assert!(!weak(pin), "{}", pin);
+        }
+    }
+
+    #[test]
+    fn test_genpin_and_pronounceable() -> Result<()> {
+        let (pin, entropy) = process_genpin(4)?;
+        assert_eq!(pin.len(), 4);
+        assert!(!weak(&pin));
+        assert!(entropy < 4.0 * 10f64.log2());
+
+        // 和逐个枚举的结果一致
+        assert_eq!(count_weak_pins(7), 18);
+        assert_eq!(count_weak_pins(8), 200_782);
+        let (_, entropy) = process_genpin(8)?;
+        assert!((entropy - (1e8 - 200_782.0f64).log2()).abs() < 1e-9);
+
+        let (pass, entropy) = process_genpass_pronounceable(4, 2, true, "-")?;
+        assert_eq!(pass.split('-').count(), 5);
+        assert!(pass.as_bytes()[0].is_ascii_uppercase());
+        assert!((entropy - (4.0 * 80f64.log2() + 2.0 * 10f64.log2())).abs() < 1e-9);
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::Result;
+use rand::{thread_rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use super::gen_pass::{choose, shuffle, LOWER, NUMBER, SYMBOL, UPPER};
+
+// 和 genpass --length 的默认值一致
+const DEFAULT_LENGTH: u8 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CharClass {
+    Upper,
+    Lower,
+    Number,
+    Symbol,
+}
+
+/// Rules a generated password must satisfy, loaded from a policy file
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PasswordPolicy {
+    #[serde(default = "default_min_length")]
+    pub min_length: u8,
+    #[serde(default = "default_max_length")]
+    pub max_length: u8,
+    /// classes the password may use
+    #[serde(default = "all_classes")]
+    pub allowed: Vec<CharClass>,
+    /// minimum number of characters from each class
+    #[serde(default)]
+    pub required: BTreeMap<CharClass, u8>,
+    /// characters that must never appear
+    #[serde(default)]
+    pub forbidden: String,
+    /// classes the first character must come from, empty means any allowed class
+    #[serde(default)]
+    pub first: Vec<CharClass>,
+    /// classes the last character must come from, empty means any allowed class
+    #[serde(default)]
+    pub last: Vec<CharClass>,
+}
+
+fn default_min_length() -> u8 {
+    8
+}
+
+fn default_max_length() -> u8 {
+    u8::MAX
+}
+
+fn all_classes() -> Vec<CharClass> {
+    vec![
+        CharClass::Upper,
+        CharClass::Lower,
+        CharClass::Number,
+        CharClass::Symbol,
+    ]
+}
+
+/// Load the named policy from a TOML or YAML file of `name -> policy` tables
+pub fn load_pass_policy(path: impl AsRef<Path>, name: &str) -> Result<PasswordPolicy> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)?;
+    let mut policies: BTreeMap<String, PasswordPolicy> =
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&content)?,
+            Some("toml") => toml::from_str(&content)?,
+            _ => return Err(anyhow::anyhow!("policy file must be .toml or .yaml")),
+        };
+    policies
+        .remove(name)
+        .ok_or_else(|| anyhow::anyhow!("policy {} not found in {}", name, path.display()))
+}
+
+/// Generate a password that satisfies the policy. An explicit `length` must be within
+/// its bounds, without one the default length is clamped into them
+pub fn process_genpass_policy(length: Option<u8>, policy: &PasswordPolicy) -> Result<String> {
+    let (min, max) = (policy.min_length, policy.max_length);
+    let length = match length {
+        Some(length) if length < min || length > max => {
+            return Err(anyhow::anyhow!(
+                "length {} is outside the policy's bounds {}..={}",
+                length,
+                min,
+                max
+            ))
+        }
+        Some(length) => length,
+        // min > max 的情况交给 generate 报错，这里不能用 clamp
+        None => DEFAULT_LENGTH.max(min).min(max),
+    };
+    let mut rng = thread_rng();
+    let password = policy.generate(&mut rng, length)?;
+    policy.check(&password)?;
+    Ok(password)
+}
+
+impl PasswordPolicy {
+    /// Characters of a class that are allowed by this policy
+    fn charset(&self, class: CharClass) -> Vec<u8> {
+        if !self.allowed.contains(&class) {
+            return Vec::new();
+        }
+        class
+            .chars()
+            .iter()
+            .copied()
+            .filter(|c| !self.forbidden.as_bytes().contains(c))
+            .collect()
+    }
+
+    fn generate(&self, rng: &mut impl RngCore, length: u8) -> Result<String> {
+        if self.min_length > self.max_length {
+            return Err(anyhow::anyhow!("min_length is greater than max_length"));
+        }
+        let length = length as usize;
+
+        let pool: Vec<u8> = self.allowed.iter().flat_map(|c| self.charset(*c)).collect();
+        if pool.is_empty() {
+            return Err(anyhow::anyhow!("policy doesn't allow any character"));
+        }
+
+        let mut remaining = self.required.clone();
+        for (class, count) in &remaining {
+            if *count > 0 && self.charset(*class).is_empty() {
+                return Err(anyhow::anyhow!("{:?} is required but not allowed", class));
+            }
+        }
+
+        let fixed = [&self.first, &self.last]
+            .iter()
+            .filter(|c| !c.is_empty())
+            .count();
+        if length < fixed.max(1) {
+            return Err(anyhow::anyhow!("length {} is too short for policy", length));
+        }
+
+        // 先确定首尾两个位置的字符，剩下的空位必须能放下还需要的字符
+        let mut free = length;
+        let first = self.pick_positional(rng, &self.first, &mut remaining, &mut free)?;
+        let last = if length > 1 {
+            self.pick_positional(rng, &self.last, &mut remaining, &mut free)?
+        } else {
+            None
+        };
+
+        let needed: usize = remaining.values().map(|c| *c as usize).sum();
+        if needed > free {
+            return Err(anyhow::anyhow!(
+                "policy can't be satisfied with length {}",
+                length
+            ));
+        }
+
+        let mut middle = Vec::with_capacity(free);
+        for (class, count) in &remaining {
+            let chars = self.charset(*class);
+            for _ in 0..*count {
+                middle.push(choose(rng, &chars));
+            }
+        }
+        while middle.len() < free {
+            middle.push(choose(rng, &pool));
+        }
+        shuffle(rng, &mut middle);
+
+        let mut password = Vec::with_capacity(length);
+        password.extend(first);
+        password.extend(middle);
+        password.extend(last);
+        Ok(String::from_utf8(password)?)
+    }
+
+    /// Pick a character for a constrained position, preferring classes still required
+    /// when the remaining free slots are tight
+    fn pick_positional(
+        &self,
+        rng: &mut impl RngCore,
+        classes: &[CharClass],
+        remaining: &mut BTreeMap<CharClass, u8>,
+        free: &mut usize,
+    ) -> Result<Option<u8>> {
+        if classes.is_empty() {
+            return Ok(None);
+        }
+        *free -= 1;
+        let needed: usize = remaining.values().map(|c| *c as usize).sum();
+        let mut candidates: Vec<CharClass> = classes
+            .iter()
+            .copied()
+            .filter(|c| !self.charset(*c).is_empty())
+            .collect();
+        if needed > *free {
+            candidates.retain(|c| remaining.get(c).copied().unwrap_or(0) > 0);
+        }
+        let chars: Vec<u8> = candidates.iter().flat_map(|c| self.charset(*c)).collect();
+        if chars.is_empty() {
+            return Err(anyhow::anyhow!("policy can't satisfy positional rules"));
+        }
+        let c = choose(rng, &chars);
+        if let Some(class) = CharClass::of(c) {
+            if let Some(count) = remaining.get_mut(&class) {
+                *count = count.saturating_sub(1);
+            }
+        }
+        Ok(Some(c))
+    }
+
+    /// Check a password against every rule in the policy
+    pub fn check(&self, password: &str) -> Result<()> {
+        let bytes = password.as_bytes();
+        if bytes.len() < self.min_length as usize || bytes.len() > self.max_length as usize {
+            return Err(anyhow::anyhow!("password length is out of bounds"));
+        }
+        let allowed: Vec<u8> = self.allowed.iter().flat_map(|c| self.charset(*c)).collect();
+        if let Some(c) = bytes.iter().find(|c| !allowed.contains(c)) {
+            return Err(anyhow::anyhow!("character {:?} is not allowed", *c as char));
+        }
+        for (class, count) in &self.required {
+            let n = bytes
+                .iter()
+                .filter(|c| CharClass::of(**c) == Some(*class))
+                .count();
+            if n < *count as usize {
+                return Err(anyhow::anyhow!("needs at least {} {:?}", count, class));
+            }
+        }
+        for (classes, c) in [(&self.first, bytes.first()), (&self.last, bytes.last())] {
+            if let Some(c) = c {
+                if !classes.is_empty() && !classes.iter().any(|cl| Some(*cl) == CharClass::of(*c)) {
+                    return Err(anyhow::anyhow!(
+                        "character {:?} is not allowed here",
+                        *c as char
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CharClass {
+    fn chars(self) -> &'static [u8] {
+        match self {
+            CharClass::Upper => UPPER,
+            CharClass::Lower => LOWER,
+            CharClass::Number => NUMBER,
+            CharClass::Symbol => SYMBOL,
+        }
+    }
+
+    fn of(c: u8) -> Option<Self> {
+        [
+            CharClass::Upper,
+            CharClass::Lower,
+            CharClass::Number,
+            CharClass::Symbol,
+        ]
+        .into_iter()
+        .find(|class| class.chars().contains(&c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_genpass_policy() -> Result<()> {
+        for name in ["legacy", "pin_like", "tight"] {
+            let policy = load_pass_policy("fixtures/policies.toml", name)?;
+            for _ in 0..200 {
+                let password = process_genpass_policy(Some(policy.max_length), &policy)?;
+                assert!(policy.check(&password).is_ok(), "{}: {}", name, password);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_yaml_policy() -> Result<()> {
+        let policy = load_pass_policy("fixtures/policies.yaml", "legacy")?;
+        assert_eq!(policy.max_length, 20);
+        assert_eq!(policy.required.get(&CharClass::Number), Some(&2));
+        let password = process_genpass_policy(Some(16), &policy)?;
+        assert!(password.as_bytes()[0].is_ascii_alphabetic());
+        assert!(!password.contains(['\'', '"']));
+        Ok(())
+    }
+
+    #[test]
+    fn test_genpass_policy_length() -> Result<()> {
+        let policy = load_pass_policy("fixtures/policies.yaml", "legacy")?;
+        // 明确指定的长度超出范围要报错，默认长度才收进范围
+        assert!(process_genpass_policy(Some(policy.max_length + 1), &policy).is_err());
+        assert!(process_genpass_policy(Some(policy.min_length - 1), &policy).is_err());
+        let password = process_genpass_policy(None, &policy)?;
+        assert_eq!(
+            password.len(),
+            DEFAULT_LENGTH.clamp(policy.min_length, policy.max_length) as usize
+        );
+        Ok(())
+    }
+}
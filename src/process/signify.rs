@@ -0,0 +1,177 @@
+use std::io::Read;
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha512};
+
+use super::minisign::{decode_base64, UNTRUSTED_PREFIX};
+
+const PK_ALG: &[u8] = b"Ed";
+const KDF_ALG: &[u8] = b"BK";
+// signify 默认的 bcrypt_pbkdf 轮数，0 表示不加密
+const KDF_ROUNDS: u32 = 42;
+// 轮数直接来自 key 文件，太大会让签名一直算下去
+const MAX_KDF_ROUNDS: u32 = 1024;
+// pkalg, kdfalg, kdfrounds, salt, checksum, keynum, seckey
+const SECRET_KEY_LEN: usize = 2 + 2 + 4 + 16 + 8 + 8 + 64;
+const PUBLIC_KEY_LEN: usize = 2 + 8 + 32;
+const SIGNATURE_LEN: usize = 2 + 8 + 64;
+
+/// Generate a signify key pair, returns the secret and public key file contents
+pub fn process_signify_keygen(passphrase: Option<&str>, comment: &str) -> Result<(String, String)> {
+    let key = SigningKey::generate(&mut OsRng);
+    let mut keynum = [0u8; 8];
+    OsRng.fill_bytes(&mut keynum);
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+
+    let mut seckey = key.to_keypair_bytes();
+    let checksum = checksum(&seckey);
+    let rounds = match passphrase {
+        Some(passphrase) => {
+            xor_stream(&mut seckey, passphrase, &salt, KDF_ROUNDS)?;
+            KDF_ROUNDS
+        }
+        None => 0,
+    };
+
+    let mut sk = Vec::with_capacity(SECRET_KEY_LEN);
+    sk.extend_from_slice(PK_ALG);
+    sk.extend_from_slice(KDF_ALG);
+    sk.extend_from_slice(&rounds.to_be_bytes());
+    sk.extend_from_slice(&salt);
+    sk.extend_from_slice(&checksum);
+    sk.extend_from_slice(&keynum);
+    sk.extend_from_slice(&seckey);
+
+    let mut pk = Vec::with_capacity(PUBLIC_KEY_LEN);
+    pk.extend_from_slice(PK_ALG);
+    pk.extend_from_slice(&keynum);
+    pk.extend_from_slice(key.verifying_key().as_bytes());
+
+    Ok((
+        format!(
+            "{}{} secret key\n{}\n",
+            UNTRUSTED_PREFIX,
+            comment,
+            STANDARD.encode(sk)
+        ),
+        format!(
+            "{}{} public key\n{}\n",
+            UNTRUSTED_PREFIX,
+            comment,
+            STANDARD.encode(pk)
+        ),
+    ))
+}
+
+/// Sign the whole message with a signify secret key, `passphrase` is only asked for if the key is encrypted
+pub fn process_signify_sign(
+    reader: &mut dyn Read,
+    secret_key: &str,
+    passphrase: &dyn Fn() -> Result<String>,
+    comment: &str,
+) -> Result<String> {
+    let sk = decode_base64(secret_key, SECRET_KEY_LEN, "secret key")?;
+    if &sk[..2] != PK_ALG || &sk[2..4] != KDF_ALG {
+        return Err(anyhow::anyhow!("unsupported signify secret key"));
+    }
+    let rounds = u32::from_be_bytes(sk[4..8].try_into()?);
+    if rounds > MAX_KDF_ROUNDS {
+        return Err(anyhow::anyhow!(
+            "bcrypt_pbkdf rounds {} is too large",
+            rounds
+        ));
+    }
+    let mut seckey: [u8; 64] = sk[40..].try_into()?;
+    if rounds > 0 {
+        xor_stream(&mut seckey, &passphrase()?, &sk[8..24], rounds)?;
+    }
+    if checksum(&seckey)[..] != sk[24..32] {
+        return Err(anyhow::anyhow!("wrong passphrase or corrupted secret key"));
+    }
+    let key = SigningKey::from_keypair_bytes(&seckey)?;
+
+    // signify 不做预哈希，签的是整个文件
+    let mut message = Vec::new();
+    reader.read_to_end(&mut message)?;
+    let mut sig = Vec::with_capacity(SIGNATURE_LEN);
+    sig.extend_from_slice(PK_ALG);
+    sig.extend_from_slice(&sk[32..40]);
+    sig.extend_from_slice(&key.sign(&message).to_bytes());
+    Ok(format!(
+        "{}{}\n{}\n",
+        UNTRUSTED_PREFIX,
+        comment,
+        STANDARD.encode(sig)
+    ))
+}
+
+/// Verify a signify signature, `public_key` is either a public key file or the bare base64 key
+pub fn process_signify_verify(
+    reader: &mut dyn Read,
+    public_key: &str,
+    signature: &str,
+) -> Result<bool> {
+    let pk = decode_base64(public_key, PUBLIC_KEY_LEN, "public key")?;
+    let sig = decode_base64(signature, SIGNATURE_LEN, "signature")?;
+    if &pk[..2] != PK_ALG || &sig[..2] != PK_ALG {
+        return Err(anyhow::anyhow!("unsupported signify key or signature"));
+    }
+    if sig[2..10] != pk[2..10] {
+        return Err(anyhow::anyhow!(
+            "verification failed: checked against wrong key"
+        ));
+    }
+    let key = VerifyingKey::from_bytes(pk[10..].try_into()?)?;
+    let mut message = Vec::new();
+    reader.read_to_end(&mut message)?;
+    let signature = Signature::from_bytes(sig[10..].try_into()?);
+    Ok(key.verify_strict(&message, &signature).is_ok())
+}
+
+fn checksum(seckey: &[u8]) -> [u8; 8] {
+    Sha512::digest(seckey)[..8].try_into().unwrap()
+}
+
+fn xor_stream(seckey: &mut [u8], passphrase: &str, salt: &[u8], rounds: u32) -> Result<()> {
+    let mut stream = vec![0u8; seckey.len()];
+    bcrypt_pbkdf::bcrypt_pbkdf(passphrase, salt, rounds, &mut stream)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    seckey.iter_mut().zip(stream).for_each(|(k, s)| *k ^= s);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signify_sign_verify() -> Result<()> {
+        let (sk, pk) = process_signify_keygen(Some("hunter2"), "rcli")?;
+        assert!(pk.starts_with("untrusted comment: rcli public key\nRW"));
+        let sig = process_signify_sign(
+            &mut &b"hello"[..],
+            &sk,
+            &|| Ok("hunter2".into()),
+            "verify with rcli.pub",
+        )?;
+        assert!(process_signify_verify(&mut &b"hello"[..], &pk, &sig)?);
+        assert!(!process_signify_verify(&mut &b"hellO"[..], &pk, &sig)?);
+        assert!(process_signify_sign(&mut &b""[..], &sk, &|| Ok("wrong".into()), "").is_err());
+
+        let (_, other) = process_signify_keygen(None, "other")?;
+        assert!(process_signify_verify(&mut &b"hello"[..], &other, &sig).is_err());
+
+        // kdfrounds 在 4..8
+        let mut tampered = STANDARD.decode(sk.lines().nth(1).unwrap())?;
+        tampered[4..8].copy_from_slice(&(MAX_KDF_ROUNDS + 1).to_be_bytes());
+        let tampered = STANDARD.encode(tampered);
+        let err = process_signify_sign(&mut &b""[..], &tampered, &|| Ok("hunter2".into()), "")
+            .unwrap_err();
+        assert!(err.to_string().contains("too large"));
+        Ok(())
+    }
+}
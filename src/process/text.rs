@@ -1,10 +1,30 @@
-use std::{fs, io::Read, path::Path};
-
-use crate::{get_reader, process_genpass, TextSignFormat};
+use std::{
+    io::{self, Read},
+    marker::PhantomData,
+    path::Path,
+};
+
+use super::key::symmetric_key;
+use crate::{get_reader, process_genpass, Key, TextSignFormat};
 use anyhow::{Ok, Result};
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use rand::rngs::OsRng;
+use ring::{
+    constant_time, hmac,
+    rand::SystemRandom,
+    signature::{
+        self, EcdsaKeyPair, EcdsaSigningAlgorithm, EcdsaVerificationAlgorithm, KeyPair, RsaKeyPair,
+        UnparsedPublicKey,
+    },
+};
+use rsa::{pkcs1::EncodeRsaPublicKey, pkcs8::EncodePrivateKey, RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha512};
+
+// 按块读取输入，内存占用和输入大小无关
+const CHUNK_SIZE: usize = 64 * 1024;
+// ring 只能用 RSA 密钥，不能生成，生成交给 rsa crate
+const RSA_BITS: usize = 2048;
 
 pub trait TextSign {
     /// Sign the data from the reader and return the signature
@@ -43,6 +63,60 @@ pub struct Ed25519Verifier {
     key: VerifyingKey,
 }
 
+/// Ed25519ph (RFC 8032): signs the SHA-512 of the input, so it can be hashed in chunks
+pub struct Ed25519phSigner {
+    key: SigningKey,
+}
+
+pub struct Ed25519phVerifier {
+    key: VerifyingKey,
+}
+
+/// HMAC-SHA256 with a shared key of any length
+pub struct HmacSha256 {
+    key: hmac::Key,
+}
+
+/// A NIST curve usable with ECDSA, signatures are ASN.1 DER like OpenSSL
+pub trait EcdsaCurve {
+    fn signing() -> &'static EcdsaSigningAlgorithm;
+    fn verification() -> &'static EcdsaVerificationAlgorithm;
+    fn format() -> TextSignFormat;
+    /// length of the uncompressed public point 0x04 || X || Y
+    fn public_key_len() -> usize;
+}
+
+pub struct P256;
+
+pub struct P384;
+
+/// ECDSA signer, the private key is PKCS#8 DER
+pub struct EcdsaSigner<C: EcdsaCurve> {
+    key: EcdsaKeyPair,
+    curve: PhantomData<C>,
+}
+
+/// ECDSA verifier, the public key is an uncompressed point
+pub struct EcdsaVerifier<C: EcdsaCurve> {
+    key: Vec<u8>,
+    curve: PhantomData<C>,
+}
+
+pub type EcdsaP256Signer = EcdsaSigner<P256>;
+pub type EcdsaP256Verifier = EcdsaVerifier<P256>;
+pub type EcdsaP384Signer = EcdsaSigner<P384>;
+pub type EcdsaP384Verifier = EcdsaVerifier<P384>;
+
+/// RSASSA-PSS with SHA-256, the private key is PKCS#8 DER
+pub struct RsaPssSigner {
+    key: RsaKeyPair,
+}
+
+/// RSASSA-PSS verifier, the public key is PKCS#1 RSAPublicKey DER
+pub struct RsaPssVerifier {
+    key: Vec<u8>,
+}
+
 pub fn process_text_sign(input: &str, key: &str, format: TextSignFormat) -> Result<String> {
     let mut reader = get_reader(input)?;
 
@@ -55,6 +129,26 @@ pub fn process_text_sign(input: &str, key: &str, format: TextSignFormat) -> Resu
             let signer = Ed25519Signer::load(key)?;
             signer.sign(&mut reader)?
         }
+        TextSignFormat::Ed25519ph => {
+            let signer = Ed25519phSigner::load(key)?;
+            signer.sign(&mut reader)?
+        }
+        TextSignFormat::HmacSha256 => {
+            let signer = HmacSha256::load(key)?;
+            signer.sign(&mut reader)?
+        }
+        TextSignFormat::EcdsaP256 => {
+            let signer = EcdsaP256Signer::load(key)?;
+            signer.sign(&mut reader)?
+        }
+        TextSignFormat::EcdsaP384 => {
+            let signer = EcdsaP384Signer::load(key)?;
+            signer.sign(&mut reader)?
+        }
+        TextSignFormat::RsaPss => {
+            let signer = RsaPssSigner::load(key)?;
+            signer.sign(&mut reader)?
+        }
     };
     let signed = URL_SAFE_NO_PAD.encode(signed);
     // println!("{}", signed);
@@ -80,6 +174,26 @@ pub fn process_text_verify(
             let verifier = Ed25519Verifier::load(key)?;
             verifier.verify(&mut reader, &sig)?
         }
+        TextSignFormat::Ed25519ph => {
+            let verifier = Ed25519phVerifier::load(key)?;
+            verifier.verify(&mut reader, &sig)?
+        }
+        TextSignFormat::HmacSha256 => {
+            let verifier = HmacSha256::load(key)?;
+            verifier.verify(&mut reader, &sig)?
+        }
+        TextSignFormat::EcdsaP256 => {
+            let verifier = EcdsaP256Verifier::load(key)?;
+            verifier.verify(&mut reader, &sig)?
+        }
+        TextSignFormat::EcdsaP384 => {
+            let verifier = EcdsaP384Verifier::load(key)?;
+            verifier.verify(&mut reader, &sig)?
+        }
+        TextSignFormat::RsaPss => {
+            let verifier = RsaPssVerifier::load(key)?;
+            verifier.verify(&mut reader, &sig)?
+        }
     };
 
     Ok(valid)
@@ -88,21 +202,24 @@ pub fn process_text_verify(
 pub fn process_generate(format: TextSignFormat) -> Result<Vec<Vec<u8>>> {
     match format {
         TextSignFormat::Blake3 => Blake3::generate(),
-        TextSignFormat::Ed25519 => Ed25519Signer::generate(),
+        // 两种签名方式用的是同一种密钥
+        TextSignFormat::Ed25519 | TextSignFormat::Ed25519ph => Ed25519Signer::generate(),
+        TextSignFormat::HmacSha256 => HmacSha256::generate(),
+        TextSignFormat::EcdsaP256 => EcdsaP256Signer::generate(),
+        TextSignFormat::EcdsaP384 => EcdsaP384Signer::generate(),
+        TextSignFormat::RsaPss => RsaPssSigner::generate(),
     }
 }
 
 impl TextSign for Blake3 {
     fn sign(&self, reader: &mut dyn Read) -> Result<Vec<u8>> {
-        // TODO: improve perf by reading in chunks
-        let mut buf = Vec::new();
-        reader.read_to_end(&mut buf)?;
-        Ok(blake3::keyed_hash(&self.key, &buf).as_bytes().to_vec())
+        Ok(self.hash(reader)?.as_bytes().to_vec())
     }
 }
 
 impl TextSign for Ed25519Signer {
     fn sign(&self, reader: &mut dyn Read) -> Result<Vec<u8>> {
+        // 纯 Ed25519 需要对消息做两遍哈希，只能整个读进来；大文件请用 ed25519ph
         let mut buf = Vec::new();
         reader.read_to_end(&mut buf)?;
         let sig = self.key.sign(&buf);
@@ -110,16 +227,57 @@ impl TextSign for Ed25519Signer {
     }
 }
 
+impl TextSign for Ed25519phSigner {
+    fn sign(&self, reader: &mut dyn Read) -> Result<Vec<u8>> {
+        let sig = self.key.sign_prehashed(prehash(reader)?, None)?;
+        Ok(sig.to_bytes().to_vec())
+    }
+}
+
+impl TextSign for HmacSha256 {
+    fn sign(&self, reader: &mut dyn Read) -> Result<Vec<u8>> {
+        Ok(self.tag(reader)?.as_ref().to_vec())
+    }
+}
+
+impl<C: EcdsaCurve> TextSign for EcdsaSigner<C> {
+    fn sign(&self, reader: &mut dyn Read) -> Result<Vec<u8>> {
+        // ring 的 ECDSA 和 RSA 都不支持预哈希，只能整个读进来
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        let sig = self
+            .key
+            .sign(&SystemRandom::new(), &buf)
+            .map_err(|_| anyhow::anyhow!("ecdsa sign failed"))?;
+        Ok(sig.as_ref().to_vec())
+    }
+}
+
+impl TextSign for RsaPssSigner {
+    fn sign(&self, reader: &mut dyn Read) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        let mut sig = vec![0; self.key.public().modulus_len()];
+        self.key
+            .sign(
+                &signature::RSA_PSS_SHA256,
+                &SystemRandom::new(),
+                &buf,
+                &mut sig,
+            )
+            .map_err(|_| anyhow::anyhow!("rsa sign failed"))?;
+        Ok(sig)
+    }
+}
+
 impl TextVerify for Blake3 {
     // 使用的时候需要显是的加一个 mut
     fn verify(&self, mut reader: impl Read, sig: &[u8]) -> Result<bool> {
-        let mut buf = Vec::new();
-        reader.read_to_end(&mut buf)?;
-        // 注意生命周期
-        let hash = blake3::keyed_hash(&self.key, &buf);
-        let hash = hash.as_bytes();
-        // hash 直到函数结束才会被 free 掉
-        Ok(hash == sig)
+        let Result::Ok(sig) = <[u8; 32]>::try_from(sig) else {
+            return Ok(false);
+        };
+        // blake3::Hash 的比较是常量时间的
+        Ok(self.hash(&mut reader)? == blake3::Hash::from(sig))
     }
 }
 
@@ -127,17 +285,73 @@ impl TextVerify for Ed25519Verifier {
     fn verify(&self, mut reader: impl Read, sig: &[u8]) -> Result<bool> {
         let mut buf = Vec::new();
         reader.read_to_end(&mut buf)?;
-        let sig = (&sig[..64]).try_into()?;
-        let sig = Signature::from_bytes(sig);
+        let sig = Signature::from_slice(sig)?;
         let ret = self.key.verify(&buf, &sig).is_ok();
         Ok(ret)
     }
 }
 
+impl TextVerify for Ed25519phVerifier {
+    fn verify(&self, mut reader: impl Read, sig: &[u8]) -> Result<bool> {
+        let sig = Signature::from_slice(sig)?;
+        let ret = self
+            .key
+            .verify_prehashed(prehash(&mut reader)?, None, &sig)
+            .is_ok();
+        Ok(ret)
+    }
+}
+
+impl TextVerify for HmacSha256 {
+    fn verify(&self, mut reader: impl Read, sig: &[u8]) -> Result<bool> {
+        let tag = self.tag(&mut reader)?;
+        Ok(constant_time::verify_slices_are_equal(tag.as_ref(), sig).is_ok())
+    }
+}
+
+impl<C: EcdsaCurve> TextVerify for EcdsaVerifier<C> {
+    fn verify(&self, mut reader: impl Read, sig: &[u8]) -> Result<bool> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        let key = UnparsedPublicKey::new(C::verification(), &self.key);
+        Ok(key.verify(&buf, sig).is_ok())
+    }
+}
+
+impl TextVerify for RsaPssVerifier {
+    fn verify(&self, mut reader: impl Read, sig: &[u8]) -> Result<bool> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        let key = UnparsedPublicKey::new(&signature::RSA_PSS_2048_8192_SHA256, &self.key);
+        Ok(key.verify(&buf, sig).is_ok())
+    }
+}
+
+/// Feed the reader to `update` in fixed-size chunks
+fn read_chunks(reader: &mut dyn Read, mut update: impl FnMut(&[u8])) -> Result<()> {
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        match reader.read(&mut buf) {
+            Result::Ok(0) => return Ok(()),
+            Result::Ok(n) => update(&buf[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+fn prehash(reader: &mut dyn Read) -> Result<Sha512> {
+    let mut hasher = Sha512::new();
+    read_chunks(reader, |chunk| hasher.update(chunk))?;
+    Ok(hasher)
+}
+
 impl KeyLoader for Blake3 {
     fn load(path: impl AsRef<Path>) -> Result<Self> {
-        let key = fs::read(path)?;
-        Self::try_new(&key)
+        match Key::load(path, TextSignFormat::Blake3, true)? {
+            Key::Symmetric(key) => Self::try_new(&key),
+            key => Err(key.mismatch("symmetric")),
+        }
     }
 }
 
@@ -166,17 +380,24 @@ impl Blake3 {
     }
 
     pub fn try_new(key: &[u8]) -> Result<Self> {
-        let key = &key[..32];
-        let key = key.try_into()?;
-        let signer = Blake3::new(key);
-        Ok(signer)
+        Ok(Blake3::new(symmetric_key(key)?))
+    }
+
+    fn hash(&self, reader: &mut dyn Read) -> Result<blake3::Hash> {
+        let mut hasher = blake3::Hasher::new_keyed(&self.key);
+        read_chunks(reader, |chunk| {
+            hasher.update(chunk);
+        })?;
+        Ok(hasher.finalize())
     }
 }
 
 impl KeyLoader for Ed25519Signer {
     fn load(path: impl AsRef<Path>) -> Result<Self> {
-        let key = fs::read(path)?;
-        Self::try_new(&key)
+        match Key::load(path, TextSignFormat::Ed25519, true)? {
+            Key::Ed25519Secret(key) => Ok(Self::new(key)),
+            key => Err(key.mismatch("ed25519 private")),
+        }
     }
 }
 
@@ -184,18 +405,15 @@ impl Ed25519Signer {
     pub fn new(key: SigningKey) -> Self {
         Self { key }
     }
-
-    pub fn try_new(key: &[u8]) -> Result<Self> {
-        let key = SigningKey::from_bytes(key.try_into()?);
-        let signer = Self::new(key);
-        Ok(signer)
-    }
 }
 
 impl KeyLoader for Ed25519Verifier {
     fn load(path: impl AsRef<Path>) -> Result<Self> {
-        let key = fs::read(path)?;
-        Self::try_new(&key)
+        // 也可以直接用私钥验证
+        match Key::load(path, TextSignFormat::Ed25519, false)?.public_key()? {
+            Key::Ed25519Public(key) => Ok(Self::new(key)),
+            key => Err(key.mismatch("ed25519 public")),
+        }
     }
 }
 
@@ -203,16 +421,178 @@ impl Ed25519Verifier {
     pub fn new(key: VerifyingKey) -> Self {
         Self { key }
     }
+}
+
+impl KeyLoader for Ed25519phSigner {
+    fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let key = Ed25519Signer::load(path)?.key;
+        Ok(Self { key })
+    }
+}
+
+impl KeyLoader for Ed25519phVerifier {
+    fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let key = Ed25519Verifier::load(path)?.key;
+        Ok(Self { key })
+    }
+}
+
+impl KeyLoader for HmacSha256 {
+    fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match Key::load(path, TextSignFormat::HmacSha256, true)? {
+            Key::Symmetric(key) => Ok(Self::new(&key)),
+            key => Err(key.mismatch("symmetric")),
+        }
+    }
+}
 
+impl KeyGenerator for HmacSha256 {
+    fn generate() -> Result<Vec<Vec<u8>>> {
+        let key = process_genpass(32, true, true, true, true)?;
+        Ok(vec![key.as_bytes().to_vec()])
+    }
+}
+
+impl HmacSha256 {
+    pub fn new(key: &[u8]) -> Self {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+        Self { key }
+    }
+
+    fn tag(&self, reader: &mut dyn Read) -> Result<hmac::Tag> {
+        let mut ctx = hmac::Context::with_key(&self.key);
+        read_chunks(reader, |chunk| ctx.update(chunk))?;
+        Ok(ctx.sign())
+    }
+}
+
+impl EcdsaCurve for P256 {
+    fn signing() -> &'static EcdsaSigningAlgorithm {
+        &signature::ECDSA_P256_SHA256_ASN1_SIGNING
+    }
+
+    fn verification() -> &'static EcdsaVerificationAlgorithm {
+        &signature::ECDSA_P256_SHA256_ASN1
+    }
+
+    fn format() -> TextSignFormat {
+        TextSignFormat::EcdsaP256
+    }
+
+    fn public_key_len() -> usize {
+        65
+    }
+}
+
+impl EcdsaCurve for P384 {
+    fn signing() -> &'static EcdsaSigningAlgorithm {
+        &signature::ECDSA_P384_SHA384_ASN1_SIGNING
+    }
+
+    fn verification() -> &'static EcdsaVerificationAlgorithm {
+        &signature::ECDSA_P384_SHA384_ASN1
+    }
+
+    fn format() -> TextSignFormat {
+        TextSignFormat::EcdsaP384
+    }
+
+    fn public_key_len() -> usize {
+        97
+    }
+}
+
+impl<C: EcdsaCurve> KeyLoader for EcdsaSigner<C> {
+    fn load(path: impl AsRef<Path>) -> Result<Self> {
+        // 曲线不对时 ring 会拒绝
+        match Key::load(path, C::format(), true)? {
+            key @ (Key::P256Secret(_) | Key::P384Secret(_)) => Self::try_new(&key.to_raw()?),
+            key => Err(key.mismatch("ecdsa private")),
+        }
+    }
+}
+
+impl<C: EcdsaCurve> KeyGenerator for EcdsaSigner<C> {
+    fn generate() -> Result<Vec<Vec<u8>>> {
+        let rng = SystemRandom::new();
+        let sk = EcdsaKeyPair::generate_pkcs8(C::signing(), &rng)
+            .map_err(|_| anyhow::anyhow!("failed to generate ecdsa key"))?;
+        let pk = Self::try_new(sk.as_ref())?
+            .key
+            .public_key()
+            .as_ref()
+            .to_vec();
+        Ok(vec![sk.as_ref().to_vec(), pk])
+    }
+}
+
+impl<C: EcdsaCurve> EcdsaSigner<C> {
+    pub fn try_new(pkcs8: &[u8]) -> Result<Self> {
+        let key = EcdsaKeyPair::from_pkcs8(C::signing(), pkcs8, &SystemRandom::new())
+            .map_err(|e| anyhow::anyhow!("invalid ecdsa private key: {}", e))?;
+        Ok(Self {
+            key,
+            curve: PhantomData,
+        })
+    }
+}
+
+impl<C: EcdsaCurve> KeyLoader for EcdsaVerifier<C> {
+    fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let key = Key::load(path, C::format(), false)?.public_key()?;
+        Self::try_new(&key.to_raw()?)
+    }
+}
+
+impl<C: EcdsaCurve> EcdsaVerifier<C> {
     pub fn try_new(key: &[u8]) -> Result<Self> {
-        let key = VerifyingKey::from_bytes(key.try_into()?)?;
-        let verifier = Self::new(key);
-        Ok(verifier)
+        // 曲线不对时 verify 只会返回 false，这里提前报错
+        if key.len() != C::public_key_len() || key[0] != 0x04 {
+            return Err(anyhow::anyhow!("invalid ecdsa public key"));
+        }
+        Ok(Self {
+            key: key.to_vec(),
+            curve: PhantomData,
+        })
     }
 }
 
-#[cfg(test)]
+impl KeyLoader for RsaPssSigner {
+    fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match Key::load(path, TextSignFormat::RsaPss, true)? {
+            key @ Key::RsaSecret(_) => Self::try_new(&key.to_raw()?),
+            key => Err(key.mismatch("rsa private")),
+        }
+    }
+}
+
+impl KeyGenerator for RsaPssSigner {
+    fn generate() -> Result<Vec<Vec<u8>>> {
+        let sk = RsaPrivateKey::new(&mut OsRng, RSA_BITS)?;
+        let pk = RsaPublicKey::from(&sk).to_pkcs1_der()?;
+        let sk = sk.to_pkcs8_der()?;
+        Ok(vec![sk.as_bytes().to_vec(), pk.as_bytes().to_vec()])
+    }
+}
+
+impl RsaPssSigner {
+    pub fn try_new(pkcs8: &[u8]) -> Result<Self> {
+        let key = RsaKeyPair::from_pkcs8(pkcs8)
+            .map_err(|e| anyhow::anyhow!("invalid rsa private key: {}", e))?;
+        Ok(Self { key })
+    }
+}
+
+impl KeyLoader for RsaPssVerifier {
+    fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match Key::load(path, TextSignFormat::RsaPss, false)?.public_key()? {
+            key @ Key::RsaPublic(_) => Ok(Self { key: key.to_raw()? }),
+            key => Err(key.mismatch("rsa public")),
+        }
+    }
+}
 
+#[cfg(test)]
 mod tests {
     use super::*;
     use anyhow::Result;
@@ -227,4 +607,81 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_blake3_key_length() {
+        assert!(Blake3::try_new(b"0123456789abcdef0123456789abcdef\n").is_ok());
+        assert!(Blake3::try_new(b"too short").is_err());
+        assert!(Blake3::try_new(b"0123456789abcdef0123456789abcdefX").is_err());
+    }
+
+    #[test]
+    fn test_blake3_chunked() -> Result<()> {
+        let blake3 = Blake3::load("fixtures/blake3.txt")?;
+        let data: Vec<u8> = (0..CHUNK_SIZE * 3 + 5).map(|i| (i % 251) as u8).collect();
+        let sig = blake3.sign(&mut &data[..])?;
+        assert_eq!(sig, blake3::keyed_hash(&blake3.key, &data).as_bytes());
+        assert!(!blake3.verify(&data[1..], &sig)?);
+        assert!(!blake3.verify(&data[..], &sig[..31])?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ed25519_sign_verify() -> Result<()> {
+        let data = b"hello";
+        let signer = Ed25519Signer::load("fixtures/ed25519.sk")?;
+        let verifier = Ed25519Verifier::load("fixtures/ed25519.pk")?;
+        let sig = signer.sign(&mut &data[..])?;
+        assert!(verifier.verify(&data[..], &sig)?);
+
+        let signer = Ed25519phSigner::load("fixtures/ed25519.sk")?;
+        let verifier = Ed25519phVerifier::load("fixtures/ed25519.pk")?;
+        let prehashed = signer.sign(&mut &data[..])?;
+        assert_ne!(prehashed, sig);
+        assert!(verifier.verify(&data[..], &prehashed)?);
+        assert!(!verifier.verify(&b"hellO"[..], &prehashed)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hmac_sign_verify() -> Result<()> {
+        let hmac = HmacSha256::new(b"key");
+        let sig = hmac.sign(&mut &b"The quick brown fox jumps over the lazy dog"[..])?;
+        assert_eq!(
+            sig,
+            hex_literal("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8")
+        );
+        assert!(hmac.verify(&b"The quick brown fox jumps over the lazy dog"[..], &sig)?);
+        assert!(!hmac.verify(&b"The quick brown fox"[..], &sig)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ecdsa_rsa_sign_verify() -> Result<()> {
+        let data = b"hello";
+        let sig = EcdsaP256Signer::load("fixtures/ecdsa-p256.sk")?.sign(&mut &data[..])?;
+        let verifier = EcdsaP256Verifier::load("fixtures/ecdsa-p256.pk")?;
+        assert!(verifier.verify(&data[..], &sig)?);
+        assert!(!verifier.verify(&b"hellO"[..], &sig)?);
+        // 曲线不匹配的密钥直接拒绝
+        assert!(EcdsaP384Signer::load("fixtures/ecdsa-p256.sk").is_err());
+        assert!(EcdsaP384Verifier::load("fixtures/ecdsa-p256.pk").is_err());
+
+        let sig = EcdsaP384Signer::load("fixtures/ecdsa-p384.sk")?.sign(&mut &data[..])?;
+        assert!(EcdsaP384Verifier::load("fixtures/ecdsa-p384.pk")?.verify(&data[..], &sig)?);
+
+        let sig = RsaPssSigner::load("fixtures/rsa-pss.sk")?.sign(&mut &data[..])?;
+        assert_eq!(sig.len(), RSA_BITS / 8);
+        let verifier = RsaPssVerifier::load("fixtures/rsa-pss.pk")?;
+        assert!(verifier.verify(&data[..], &sig)?);
+        assert!(!verifier.verify(&b"hellO"[..], &sig)?);
+        Ok(())
+    }
+
+    fn hex_literal(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
 }
@@ -1,5 +1,9 @@
 use anyhow::Result;
-use std::{fs::File, io::Read};
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+};
 
 pub fn get_reader(input: &str) -> Result<Box<dyn Read>> {
     let reader: Box<dyn Read> = if input == "-" {
@@ -11,9 +15,43 @@ pub fn get_reader(input: &str) -> Result<Box<dyn Read>> {
     Ok(reader)
 }
 
+pub fn get_writer(output: &str) -> Result<Box<dyn Write>> {
+    let writer: Box<dyn Write> = if output == "-" {
+        Box::new(std::io::stdout())
+    } else {
+        Box::new(File::create(output)?)
+    };
+
+    Ok(writer)
+}
+
+/// Write a private key, the file is made readable by the owner only before anything is written
+pub fn write_private(path: impl AsRef<Path>, data: &[u8]) -> Result<()> {
+    let mut file = File::create(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(data)?;
+    Ok(())
+}
+
 pub fn get_content(input: &str) -> Result<Vec<u8>> {
     let mut reader = get_reader(input)?;
     let mut buf = Vec::new();
     reader.read_to_end(&mut buf)?;
     Ok(buf)
 }
+
+/// Read a secret from the given env var, or prompt for it on the terminal
+pub fn read_secret(prompt: &str, env: &str) -> Result<String> {
+    if let Ok(secret) = std::env::var(env) {
+        return Ok(secret);
+    }
+    let secret = rpassword::prompt_password(prompt)?;
+    if secret.is_empty() {
+        return Err(anyhow::anyhow!("secret can't be empty"));
+    }
+    Ok(secret)
+}